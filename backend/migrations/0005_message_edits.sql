@@ -0,0 +1,6 @@
+-- Bewerken en verwijderen van clubberichten.
+-- Verwijderde berichten blijven als tombstone staan (lege, versleutelde content),
+-- zodat reply-context en tellingen kloppen.
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS edited_at TIMESTAMPTZ;
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS deleted_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
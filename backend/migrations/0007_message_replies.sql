@@ -0,0 +1,4 @@
+-- Replies binnen clubchat. Verwijderde berichten blijven als tombstone bestaan,
+-- dus de parent verdwijnt alleen als de hele club (of rij) echt weg is.
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS reply_to_id INTEGER REFERENCES club_messages(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_club_messages_reply_to ON club_messages(reply_to_id) WHERE reply_to_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Tot welk bericht een lid de clubchat gelezen heeft. NULL = nog niks gelezen sinds joined_at.
+ALTER TABLE club_memberships ADD COLUMN IF NOT EXISTS last_read_message_id INTEGER REFERENCES club_messages(id) ON DELETE SET NULL;
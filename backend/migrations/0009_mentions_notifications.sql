@@ -0,0 +1,22 @@
+-- 1. Mentions: opgeloste user ids naast de versleutelde content
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS mentioned_user_ids INTEGER[] NOT NULL DEFAULT '{}';
+
+-- 2. Notificaties
+DO $$ BEGIN
+    CREATE TYPE notification_kind AS ENUM ('MENTION');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    club_id INTEGER REFERENCES clubs(id) ON DELETE CASCADE,
+    message_id INTEGER REFERENCES club_messages(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_time ON notifications(user_id, created_at DESC);
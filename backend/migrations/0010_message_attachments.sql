@@ -0,0 +1,17 @@
+-- Bijlagen bij clubberichten. De bestanden zelf staan versleuteld op schijf onder
+-- assets/club_data/<club_id>/attachments; hier alleen de metadata.
+-- message_id is NULL zolang de upload nog niet aan een bericht gekoppeld is.
+CREATE TABLE IF NOT EXISTS message_attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    message_id INTEGER REFERENCES club_messages(id) ON DELETE CASCADE,
+    uploaded_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    file_name TEXT NOT NULL, -- Versleuteld, net als de berichttekst
+    mime_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_path TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_attachments_message ON message_attachments(message_id);
+CREATE INDEX IF NOT EXISTS idx_message_attachments_pending ON message_attachments(uploaded_by, created_at) WHERE message_id IS NULL;
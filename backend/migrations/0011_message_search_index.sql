@@ -0,0 +1,13 @@
+-- Blind index voor zoeken in versleutelde chat: per bericht de HMAC van elk genormaliseerd woord.
+-- De sleutel (SEARCH_INDEX_KEY) staat los van ENCRYPTION_KEY; plaintext komt nooit in de database.
+CREATE TABLE IF NOT EXISTS message_search_tokens (
+    message_id INTEGER NOT NULL REFERENCES club_messages(id) ON DELETE CASCADE,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    token BYTEA NOT NULL,
+    PRIMARY KEY (message_id, token)
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_search_tokens_lookup ON message_search_tokens(club_id, token);
+
+-- Berichten van vóór deze migratie worden bij het opstarten alsnog geïndexeerd
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS search_indexed BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,43 @@
+-- End-to-end versleutelde clubchat (opt-in per club).
+-- De server bewaart alleen publieke sleutels en per lid ingepakte clubsleutels; de clubsleutel zelf ziet hij nooit.
+
+-- 1. Publieke X25519 sleutel per gebruiker (base64)
+CREATE TABLE IF NOT EXISTS user_public_keys (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    public_key TEXT NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 2. E2E status per club
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS e2e_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS e2e_key_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS e2e_rotation_needed BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 3. Clubsleutel per lid en versie, ingepakt met de publieke sleutel van dat lid
+CREATE TABLE IF NOT EXISTS club_key_envelopes (
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key_version INTEGER NOT NULL,
+    wrapped_key TEXT NOT NULL,
+    wrapped_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (club_id, user_id, key_version)
+);
+
+-- 4. Berichten met een sleutelversie zijn client-side versleuteld en worden door de server niet aangeraakt
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS e2e_key_version INTEGER;
+
+-- 5. Wie de club verlaat (verwijderd of geband) heeft de huidige sleutel nog: markeer de club voor rotatie
+CREATE OR REPLACE FUNCTION flag_club_e2e_rotation() RETURNS trigger AS $$
+BEGIN
+    IF OLD.status = 'ACTIVE'::member_status AND (TG_OP = 'DELETE' OR NEW.status <> 'ACTIVE'::member_status) THEN
+        UPDATE clubs SET e2e_rotation_needed = TRUE WHERE id = OLD.club_id AND e2e_enabled;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_club_e2e_member_removed ON club_memberships;
+CREATE TRIGGER trg_club_e2e_member_removed
+    AFTER DELETE OR UPDATE OF status ON club_memberships
+    FOR EACH ROW EXECUTE FUNCTION flag_club_e2e_rotation();
@@ -0,0 +1,40 @@
+-- 1. Eén-op-één gesprekken. user_a_id is altijd de laagste id, zodat elk paar maar één gesprek heeft.
+CREATE TABLE IF NOT EXISTS conversations (
+    id SERIAL PRIMARY KEY,
+    user_a_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_b_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    last_message_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT conversation_users_ordered CHECK (user_a_id < user_b_id),
+    CONSTRAINT unique_conversation_pair UNIQUE (user_a_id, user_b_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_user_b ON conversations(user_b_id);
+
+-- 2. Berichten, versleuteld net als club_messages
+CREATE TABLE IF NOT EXISTS direct_messages (
+    id SERIAL PRIMARY KEY,
+    conversation_id INTEGER NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    sender_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_direct_messages_conversation_time ON direct_messages(conversation_id, created_at DESC, id DESC);
+
+-- 3. Wie je mag DM'en: iedereen, of alleen mensen met wie je in een club zit
+DO $$ BEGIN
+    CREATE TYPE dm_policy AS ENUM ('EVERYONE', 'SHARED_CLUBS');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS dm_policy dm_policy NOT NULL DEFAULT 'EVERYONE';
+
+-- 4. Geblokkeerde gebruikers: geen DM's meer in beide richtingen
+CREATE TABLE IF NOT EXISTS user_blocks (
+    blocker_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    blocked_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (blocker_id, blocked_id)
+);
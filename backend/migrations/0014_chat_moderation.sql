@@ -0,0 +1,21 @@
+-- 1. Slow mode: minimaal aantal seconden tussen twee berichten van hetzelfde lid (0 = uit)
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS slow_mode_seconds INTEGER NOT NULL DEFAULT 0;
+
+DO $$ BEGIN
+    ALTER TABLE clubs ADD CONSTRAINT slow_mode_range CHECK (slow_mode_seconds BETWEEN 0 AND 3600);
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- Laatste bericht van een lid opzoeken voor slow mode
+CREATE INDEX IF NOT EXISTS idx_club_messages_club_user_time ON club_messages(club_id, user_id, created_at DESC);
+
+-- 2. Dempen per lid, tot een bepaald moment
+ALTER TABLE club_memberships ADD COLUMN IF NOT EXISTS muted_until TIMESTAMPTZ;
+ALTER TABLE club_memberships ADD COLUMN IF NOT EXISTS muted_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+-- 3. Vastgepinde berichten
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS pinned_at TIMESTAMPTZ;
+ALTER TABLE club_messages ADD COLUMN IF NOT EXISTS pinned_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_club_messages_pinned ON club_messages(club_id, pinned_at DESC) WHERE pinned_at IS NOT NULL;
@@ -0,0 +1,26 @@
+-- 1. Zichtbaarheid: iedereen kan joinen, eerst een verzoek, of alleen met een uitnodiging
+DO $$ BEGIN
+    CREATE TYPE club_visibility AS ENUM ('PUBLIC', 'REQUEST', 'INVITE_ONLY');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS visibility club_visibility NOT NULL DEFAULT 'PUBLIC';
+
+-- 2. Openstaande verzoeken. Alles wat op ACTIVE filtert (chat, leden, unread) sluit PENDING vanzelf uit.
+ALTER TYPE member_status ADD VALUE IF NOT EXISTS 'PENDING';
+
+-- 3. Uitnodigingscodes met verloopdatum en maximaal aantal keer gebruiken
+CREATE TABLE IF NOT EXISTS club_invites (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    code TEXT NOT NULL UNIQUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    max_uses INTEGER, -- NULL = onbeperkt
+    use_count INTEGER NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_invites_club ON club_invites(club_id);
@@ -0,0 +1,25 @@
+-- Oude slugs van hernoemde clubs blijven werken
+CREATE TABLE IF NOT EXISTS club_slug_history (
+    slug TEXT PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_slug_history_club ON club_slug_history(club_id);
+
+-- Bij een nieuwe slug de oude bewaren; neemt de club een eerdere slug terug, dan is dat geen historie meer
+CREATE OR REPLACE FUNCTION record_club_slug_history() RETURNS trigger AS $$
+BEGIN
+    IF NEW.slug IS DISTINCT FROM OLD.slug THEN
+        DELETE FROM club_slug_history WHERE slug = NEW.slug;
+        INSERT INTO club_slug_history (slug, club_id) VALUES (OLD.slug, OLD.id)
+        ON CONFLICT (slug) DO UPDATE SET club_id = EXCLUDED.club_id, created_at = CURRENT_TIMESTAMP;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_club_slug_history ON clubs;
+CREATE TRIGGER trg_club_slug_history
+    AFTER UPDATE OF slug ON clubs
+    FOR EACH ROW EXECUTE FUNCTION record_club_slug_history();
@@ -0,0 +1,2 @@
+-- Gearchiveerde clubs zijn alleen-lezen: geen nieuwe berichten of events
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Tags/categorieën om clubs te vinden, bijv. {speciaalbier, utrecht}
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS idx_clubs_tags ON clubs USING GIN (tags);
+CREATE INDEX IF NOT EXISTS idx_club_memberships_club_status ON club_memberships(club_id, status);
@@ -0,0 +1,30 @@
+-- Coördinaten voor clubs en events (WGS84, graden). Afstanden rekenen we zelf uit, geen PostGIS nodig.
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION;
+ALTER TABLE clubs ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION;
+
+ALTER TABLE events ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION;
+-- Proeverij bij iemand thuis: buitenstaanders zien alleen de buurt (~1 km), geen adres
+ALTER TABLE events ADD COLUMN IF NOT EXISTS location_hidden BOOLEAN NOT NULL DEFAULT FALSE;
+
+DO $$ BEGIN
+    ALTER TABLE clubs ADD CONSTRAINT clubs_coordinates_valid CHECK (
+        (latitude IS NULL AND longitude IS NULL)
+        OR (latitude BETWEEN -90 AND 90 AND longitude BETWEEN -180 AND 180)
+    );
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+    ALTER TABLE events ADD CONSTRAINT events_coordinates_valid CHECK (
+        (latitude IS NULL AND longitude IS NULL)
+        OR (latitude BETWEEN -90 AND 90 AND longitude BETWEEN -180 AND 180)
+    );
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- Voorfilter op breedtegraad voordat de afstand wordt berekend
+CREATE INDEX IF NOT EXISTS idx_clubs_latitude ON clubs(latitude) WHERE latitude IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_events_latitude ON events(latitude) WHERE latitude IS NOT NULL;
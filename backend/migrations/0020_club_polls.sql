@@ -0,0 +1,32 @@
+-- Peilingen binnen een club, bijv. welk bier de volgende proeverij krijgt
+CREATE TABLE IF NOT EXISTS club_polls (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    question TEXT NOT NULL,
+    multiple_choice BOOLEAN NOT NULL DEFAULT FALSE,
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE, -- Stemmen tellen wel, maar wie wat stemde is niet op te vragen
+    closes_at TIMESTAMPTZ,                    -- Null = open tot iemand hem sluit
+    closed_at TIMESTAMPTZ,                    -- Handmatig gesloten
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_polls_club ON club_polls(club_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS club_poll_options (
+    id SERIAL PRIMARY KEY,
+    poll_id INTEGER NOT NULL REFERENCES club_polls(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    UNIQUE (poll_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS club_poll_votes (
+    poll_id INTEGER NOT NULL REFERENCES club_polls(id) ON DELETE CASCADE,
+    option_id INTEGER NOT NULL REFERENCES club_poll_options(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (option_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_poll_votes_poll_user ON club_poll_votes(poll_id, user_id);
@@ -0,0 +1,38 @@
+-- Gedeelde kelder van een club: welke flessen er nog liggen en wie wat gepakt heeft
+CREATE TABLE IF NOT EXISTS club_cellar_entries (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    beer_id UUID NOT NULL REFERENCES beers(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+    vintage INTEGER,          -- Jaargang, bijv. 2021
+    bottled_on DATE,
+    best_before DATE,
+    storage_location TEXT,    -- "Kelder Jan, plank 2"
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_cellar_entries_club ON club_cellar_entries(club_id);
+
+DO $$ BEGIN
+    CREATE TYPE cellar_change_kind AS ENUM ('CHECK_IN', 'CHECK_OUT', 'CORRECTION');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- Elke wijziging in de voorraad, nieuwste eerst op te vragen
+CREATE TABLE IF NOT EXISTS club_cellar_log (
+    id SERIAL PRIMARY KEY,
+    entry_id INTEGER NOT NULL REFERENCES club_cellar_entries(id) ON DELETE CASCADE,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    kind cellar_change_kind NOT NULL,
+    quantity_change INTEGER NOT NULL,  -- Positief bij inchecken, negatief bij uitchecken
+    quantity_after INTEGER NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_cellar_log_club_time ON club_cellar_log(club_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_club_cellar_log_entry_time ON club_cellar_log(entry_id, created_at DESC);
@@ -0,0 +1,60 @@
+-- Kasboek per club. Alle bedragen in centen (BIGINT), nooit floats.
+
+-- 1. Terugkerende contributie: elke periode betaalt elk actief lid `amount_cents` aan `payee_id`
+CREATE TABLE IF NOT EXISTS club_dues (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    payee_id INTEGER NOT NULL REFERENCES users(id), -- Penningmeester
+    description TEXT NOT NULL,
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    interval_months INTEGER NOT NULL CHECK (interval_months BETWEEN 1 AND 12),
+    -- Termijnen rekenen vanaf de eerste vervaldatum. Steeds een maand bij de vorige optellen zakt
+    -- 31 januari via 28 februari blijvend naar de 28e; eerste termijn + n maanden geeft weer 31 maart.
+    first_due_on DATE NOT NULL,
+    periods_charged INTEGER NOT NULL DEFAULT 0,
+    next_due_on DATE NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_dues_next ON club_dues(next_due_on) WHERE active;
+
+-- 2. Uitgaven: iemand heeft betaald, de deelnemers dragen elk hun deel
+CREATE TABLE IF NOT EXISTS club_expenses (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    paid_by INTEGER NOT NULL REFERENCES users(id),
+    description TEXT NOT NULL,
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    event_id INTEGER REFERENCES events(id) ON DELETE SET NULL,
+    dues_id INTEGER REFERENCES club_dues(id) ON DELETE SET NULL, -- Gezet = automatisch geboekte contributie
+    dues_period DATE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (dues_id, dues_period)
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_expenses_club_time ON club_expenses(club_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS club_expense_shares (
+    expense_id INTEGER NOT NULL REFERENCES club_expenses(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    share_cents BIGINT NOT NULL CHECK (share_cents >= 0),
+    PRIMARY KEY (expense_id, user_id)
+);
+
+-- 3. Terugbetalingen tussen leden
+CREATE TABLE IF NOT EXISTS club_settlements (
+    id SERIAL PRIMARY KEY,
+    club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    from_user_id INTEGER NOT NULL REFERENCES users(id),
+    to_user_id INTEGER NOT NULL REFERENCES users(id),
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    note TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    CHECK (from_user_id <> to_user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_club_settlements_club_time ON club_settlements(club_id, created_at DESC);
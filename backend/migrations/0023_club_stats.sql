@@ -0,0 +1,3 @@
+-- Voor clubStats: reviews per gebruiker en RSVPs per gebruiker
+CREATE INDEX IF NOT EXISTS idx_reviews_user_time ON reviews(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_event_attendees_user ON event_attendees(user_id) WHERE status = 'GOING';
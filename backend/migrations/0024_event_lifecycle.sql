@@ -0,0 +1,20 @@
+-- Events kunnen aangepast en geannuleerd worden; wie zich heeft aangemeld krijgt een notificatie
+DO $$ BEGIN
+    CREATE TYPE event_status AS ENUM ('SCHEDULED', 'CANCELLED');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE events ADD COLUMN IF NOT EXISTS status event_status NOT NULL DEFAULT 'SCHEDULED';
+ALTER TABLE events ADD COLUMN IF NOT EXISTS cancel_reason TEXT;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+
+-- Nieuwe waarden worden in deze migratie nog niet gebruikt, dus dit mag binnen de transactie
+ALTER TYPE notification_kind ADD VALUE IF NOT EXISTS 'EVENT_UPDATED';
+ALTER TYPE notification_kind ADD VALUE IF NOT EXISTS 'EVENT_CANCELLED';
+ALTER TYPE notification_kind ADD VALUE IF NOT EXISTS 'EVENT_DELETED';
+
+-- SET NULL zodat de notificatie over een verwijderd event blijft staan; `detail` bewaart dan de titel
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS event_id INTEGER REFERENCES events(id) ON DELETE SET NULL;
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS detail TEXT;
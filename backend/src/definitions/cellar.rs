@@ -0,0 +1,54 @@
+use async_graphql::{SimpleObject, InputObject, Enum};
+use serde::{Serialize, Deserialize};
+use sqlx::types::Uuid;
+use time::{Date, OffsetDateTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "cellar_change_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CellarChangeKind {
+    CheckIn,
+    CheckOut,
+    Correction, // Telling aangepast door OWNER/MOD
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct CellarEntry {
+    pub id: i32,
+    pub club_id: i32,
+    pub beer_id: Uuid,
+    pub beer_name: String,
+    pub beer_brewery: Option<String>,
+    pub quantity: i32,
+    pub vintage: Option<i32>,
+    pub bottled_on: Option<Date>,
+    pub best_before: Option<Date>,
+    pub storage_location: Option<String>,
+    pub created_by: Option<i32>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct CellarLogEntry {
+    pub id: i32,
+    pub entry_id: i32,
+    pub beer_name: String,
+    pub user_id: Option<i32>,
+    pub user_display_name: Option<String>,
+    pub kind: CellarChangeKind,
+    pub quantity_change: i32,
+    pub quantity_after: i32,
+    pub note: Option<String>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(InputObject)]
+pub struct AddCellarEntryInput {
+    pub club_id: i32,
+    pub beer_id: Uuid,
+    pub quantity: i32,
+    pub vintage: Option<i32>,
+    pub bottled_on: Option<Date>,
+    pub best_before: Option<Date>,
+    pub storage_location: Option<String>,
+    pub note: Option<String>, // Komt in de geschiedenis
+}
@@ -4,6 +4,7 @@ use sqlx::types::Uuid;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+#[graphql(complex)] // reactions worden per viewer opgehaald, zie schema
 pub struct ClubMessage {
     pub id: i32,
     pub club_id: i32,
@@ -17,8 +18,14 @@ pub struct ClubMessage {
                          // We should maybe rename struct field to 'encrypted_content' or handle decryption manually.
                          // Let's use 'content' matching DB, but note it might be encrypted. 
                          // Ideally we use a resolver method to expose 'decrypted_content'.
-    #[graphql(skip)]
-    pub created_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>, // Om berichten per tijd te tonen; de cursors zijn ondoorzichtig
+    pub edited_at: Option<OffsetDateTime>,
+    pub deleted_at: Option<OffsetDateTime>, // Tombstone: content is dan leeg
+    pub reply_to_id: Option<i32>,
+    pub mentioned_user_ids: Vec<i32>,
+    #[graphql(name = "e2eKeyVersion")]
+    pub e2e_key_version: Option<i32>, // Gezet: content is ciphertext van de client, versleuteld met deze clubsleutel
+    pub pinned_at: Option<OffsetDateTime>,
     
     // Extra fields for UI optimization if we join user
     #[sqlx(default)] 
@@ -31,4 +38,74 @@ pub struct ClubMessage {
 pub struct SendMessageInput {
     pub club_id: i32,
     pub content: String, // Plaintext from client
+    pub reply_to_id: Option<i32>,
+    pub attachment_ids: Option<Vec<Uuid>>, // Eerder geüpload via uploadAttachment
+    #[graphql(name = "e2eKeyVersion")]
+    pub e2e_key_version: Option<i32>, // Verplicht in E2E clubs, content is dan ciphertext
+    pub mentioned_user_ids: Option<Vec<i32>>, // Alleen in E2E clubs: de server kan de tekst niet lezen
+}
+
+#[derive(InputObject)]
+pub struct EditMessageInput {
+    pub message_id: i32,
+    pub content: String, // Plaintext from client
+    #[graphql(name = "e2eKeyVersion")]
+    pub e2e_key_version: Option<i32>, // Zie SendMessageInput
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubMessagePage {
+    pub messages: Vec<ClubMessage>, // Nieuwste eerst
+    pub has_more: bool,             // Nog meer berichten in de richting waarin je bladert
+    pub start_cursor: Option<String>, // Nieuwste bericht van deze pagina, geef mee als `after`
+    pub end_cursor: Option<String>,   // Oudste bericht van deze pagina, geef mee als `before`
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: i64,
+    pub reacted_by_me: bool,
+}
+
+// Citaat van het bericht waarop gereageerd wordt
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MessagePreview {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub user_display_name: Option<String>,
+    pub content: String, // Ingekort, leeg als het bericht verwijderd is. Bij E2E de volledige ciphertext
+    pub is_deleted: bool,
+    #[graphql(name = "e2eKeyVersion")]
+    pub e2e_key_version: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct ReadReceipt {
+    pub user_id: i32,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct PresenceMember {
+    pub user_id: i32,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubPresence {
+    pub club_id: i32,
+    pub online: Vec<PresenceMember>,
+    pub typing: Vec<PresenceMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MessageAttachment {
+    pub id: Uuid,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub url: String, // Download met dezelfde Authorization header als /graphql
 }
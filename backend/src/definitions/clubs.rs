@@ -1,6 +1,5 @@
 use async_graphql::{SimpleObject, InputObject, Enum};
 use serde::{Serialize, Deserialize};
-use sqlx::types::Uuid;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
@@ -11,14 +10,35 @@ pub enum UserRole {
     Member,
 }
 
+impl UserRole {
+    /// Hoger is meer rechten. Je kunt alleen iemand met een lagere rang beheren.
+    pub fn rank(self) -> u8 {
+        match self {
+            UserRole::Owner => 3,
+            UserRole::Mod => 2,
+            UserRole::Member => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
 #[sqlx(type_name = "member_status", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MemberStatus {
     Active,
     Banned,
+    Pending, // Verzoek tot lidmaatschap, nog niet goedgekeurd
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "club_visibility", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClubVisibility {
+    Public,     // Iedereen kan direct lid worden
+    Request,    // Lid worden na goedkeuring door OWNER/MOD
+    InviteOnly, // Alleen met een uitnodigingscode
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+#[graphql(complex)] // unreadCount hangt af van de viewer, zie schema
 pub struct Club {
     pub id: i32,
     pub name: String,
@@ -30,6 +50,18 @@ pub struct Club {
     pub image_path: Option<String>,
     #[graphql(skip)]
     pub created_at: Option<OffsetDateTime>,
+    #[graphql(name = "e2eEnabled")]
+    pub e2e_enabled: bool,          // Berichten worden door de clients zelf versleuteld
+    #[graphql(name = "e2eKeyVersion")]
+    pub e2e_key_version: i32,       // Huidige clubsleutel, 0 zolang E2E uit staat
+    #[graphql(name = "e2eRotationNeeded")]
+    pub e2e_rotation_needed: bool,  // Er is iemand vertrokken; eerst een nieuwe sleutel rondsturen
+    pub slow_mode_seconds: i32,     // Minimaal aantal seconden tussen berichten van een lid, 0 = uit
+    pub visibility: ClubVisibility,
+    pub archived_at: Option<OffsetDateTime>, // Gezet = alleen-lezen
+    pub tags: Vec<String>,                   // Kleine letters, zonder accenten
+    pub latitude: Option<f64>,               // Waar de club meestal samenkomt
+    pub longitude: Option<f64>,
 }
 
 #[derive(InputObject)]
@@ -37,16 +69,58 @@ pub struct CreateClubInput {
     pub name: String,
     pub description: Option<String>,
     pub image_url: Option<String>, // Client can upload image separately or provide URL
+    pub visibility: Option<ClubVisibility>, // Standaard PUBLIC
+    pub tags: Option<Vec<String>>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Alleen ingevulde velden worden aangepast. Een lege beschrijving wist hem.
+#[derive(InputObject)]
+pub struct UpdateClubInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub tags: Option<Vec<String>>, // Vervangt de hele lijst
+    pub latitude: Option<f64>,     // Samen met longitude
+    pub longitude: Option<f64>,
+    pub clear_coordinates: Option<bool>, // Haalt de locatie weg; niet samen met latitude/longitude
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ClubSort {
+    Newest,
+    MostMembers,
+    MostActive, // Meeste berichten in de afgelopen 30 dagen
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubPage {
+    pub clubs: Vec<Club>,
+    pub has_more: bool, // Vraag de volgende pagina op met offset + clubs.len()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct NearbyClub {
+    pub club: Club,
+    pub distance_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubTagCount {
+    pub tag: String,
+    pub club_count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
-pub struct ClubMember {
+pub struct ClubInvite {
+    pub id: i32,
     pub club_id: i32,
-    pub user_id: i32,
-    pub role: UserRole,
-    pub status: MemberStatus,
-    #[graphql(skip)]
-    pub joined_at: Option<OffsetDateTime>,
+    pub code: String,
+    pub expires_at: OffsetDateTime,
+    pub max_uses: Option<i32>, // Null = onbeperkt
+    pub use_count: i32,
+    pub created_at: Option<OffsetDateTime>,
 }
 
 // Voor detail view, met user info
@@ -58,4 +132,6 @@ pub struct ClubMemberWithUser {
     pub avatar_url: Option<String>,
     pub role: UserRole,
     pub status: MemberStatus,
+    pub joined_at: Option<OffsetDateTime>,
+    pub muted_until: Option<OffsetDateTime>, // In het verleden betekent niet meer gedempt
 }
@@ -0,0 +1,51 @@
+use async_graphql::{SimpleObject, InputObject, Enum};
+use serde::{Serialize, Deserialize};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "dm_policy", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DmPolicy {
+    Everyone,
+    SharedClubs, // Alleen mensen met wie je samen in een club zit
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct DirectMessage {
+    pub id: i32,
+    pub conversation_id: i32,
+    pub sender_id: Option<i32>, // Null als de afzender zijn account verwijderd heeft
+    pub content: String, // Versleuteld in de DB, ontsleuteld in de resolver
+    pub created_at: Option<OffsetDateTime>,
+}
+
+// Gesprek vanuit het oogpunt van de ingelogde gebruiker
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct Conversation {
+    pub id: i32,
+    pub other_user_id: i32,
+    pub other_display_name: String,
+    pub other_avatar_url: Option<String>,
+    pub last_message_at: Option<OffsetDateTime>,
+    pub last_message: Option<String>, // Ingekort en ontsleuteld
+    pub last_message_sender_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DirectMessagePage {
+    pub messages: Vec<DirectMessage>, // Nieuwste eerst
+    pub has_more: bool,
+    pub end_cursor: Option<String>, // Oudste bericht van deze pagina, geef mee als `before`
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct BlockedUser {
+    pub user_id: i32,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct SendDirectMessageInput {
+    pub recipient_id: i32,
+    pub content: String, // Plaintext from client
+}
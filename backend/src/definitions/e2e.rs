@@ -0,0 +1,24 @@
+use async_graphql::{SimpleObject, InputObject};
+use serde::{Serialize, Deserialize};
+
+// Lid van een club met zijn publieke sleutel, om de clubsleutel voor in te pakken
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct MemberPublicKey {
+    pub user_id: i32,
+    pub display_name: String,
+    pub public_key: Option<String>, // Null als het lid nog geen sleutel gepubliceerd heeft
+    pub has_current_key: bool,      // false: iemand met de sleutel moet hem nog delen
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct ClubKeyEnvelope {
+    pub key_version: i32,
+    pub wrapped_key: String, // Alleen te openen met de private key van de ontvanger
+    pub wrapped_by: Option<i32>,
+}
+
+#[derive(InputObject)]
+pub struct KeyEnvelopeInput {
+    pub user_id: i32,
+    pub wrapped_key: String,
+}
@@ -1,6 +1,5 @@
 use async_graphql::{SimpleObject, InputObject, Enum};
 use serde::{Serialize, Deserialize};
-use sqlx::types::Uuid;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
@@ -11,6 +10,13 @@ pub enum RsvpStatus {
     NotGoing,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "event_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventStatus {
+    Scheduled,
+    Cancelled,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
 pub struct Event {
     pub id: i32,
@@ -25,6 +31,17 @@ pub struct Event {
     pub created_by: Option<i32>,
     #[graphql(skip)]
     pub created_at: Option<OffsetDateTime>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub location_hidden: bool, // Buitenstaanders krijgen afgeronde coördinaten en geen adres
+    pub status: EventStatus,
+    pub cancel_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct NearbyEvent {
+    pub event: Event,
+    pub distance_km: f64,
 }
 
 #[derive(InputObject)]
@@ -35,11 +52,21 @@ pub struct CreateEventInput {
     pub location: Option<String>,
     pub starts_at: OffsetDateTime,
     pub ends_at: Option<OffsetDateTime>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub location_hidden: Option<bool>, // Standaard FALSE
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
-pub struct EventAttendee {
-    pub event_id: i32,
-    pub user_id: i32,
-    pub status: RsvpStatus,
+/// Alleen de meegegeven velden veranderen. Een lege description of location wist die.
+#[derive(InputObject)]
+pub struct UpdateEventInput {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: Option<OffsetDateTime>,
+    pub ends_at: Option<OffsetDateTime>,
+    pub latitude: Option<f64>, // Samen met longitude
+    pub longitude: Option<f64>,
+    pub clear_coordinates: Option<bool>, // Haalt de exacte plek weg; niet samen met latitude/longitude
+    pub location_hidden: Option<bool>,
 }
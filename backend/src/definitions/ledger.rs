@@ -0,0 +1,98 @@
+use async_graphql::{SimpleObject, InputObject};
+use serde::{Serialize, Deserialize};
+use time::{Date, OffsetDateTime};
+
+// Alle bedragen zijn centen. Een positief saldo betekent dat je geld terugkrijgt.
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubExpense {
+    pub id: i32,
+    pub club_id: i32,
+    pub paid_by: i32,
+    pub description: String,
+    pub amount_cents: i64,
+    pub event_id: Option<i32>,
+    pub dues_id: Option<i32>, // Gezet bij automatisch geboekte contributie
+    pub created_by: Option<i32>,
+    pub created_at: Option<OffsetDateTime>,
+    pub shares: Vec<ExpenseShare>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ExpenseShare {
+    pub user_id: i32,
+    pub share_cents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct ClubSettlement {
+    pub id: i32,
+    pub club_id: i32,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub amount_cents: i64,
+    pub note: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct ClubDues {
+    pub id: i32,
+    pub club_id: i32,
+    pub payee_id: i32,
+    pub description: String,
+    pub amount_cents: i64, // Per lid per periode
+    pub interval_months: i32,
+    pub next_due_on: Date,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MemberBalance {
+    pub user_id: i32,
+    pub display_name: String,
+    pub balance_cents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Transfer {
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubBalances {
+    pub balances: Vec<MemberBalance>, // Alleen wie niet op nul staat
+    pub transfers: Vec<Transfer>,     // Zo min mogelijk overboekingen waarmee iedereen weer op nul staat
+}
+
+#[derive(InputObject)]
+pub struct AddExpenseInput {
+    pub club_id: i32,
+    pub description: String,
+    pub amount_cents: i64,
+    pub paid_by: Option<i32>,               // Standaard jijzelf; voor een ander alleen OWNER/MOD
+    pub event_id: Option<i32>,              // Zonder participantIds: verdelen over de GOING RSVPs
+    pub participant_ids: Option<Vec<i32>>,  // Gelijk verdeeld
+}
+
+#[derive(InputObject)]
+pub struct RecordSettlementInput {
+    pub club_id: i32,
+    pub from_user_id: i32,
+    pub to_user_id: Option<i32>, // Standaard jijzelf: de ontvanger bevestigt dat er betaald is
+    pub amount_cents: i64,
+    pub note: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct CreateDuesInput {
+    pub club_id: i32,
+    pub description: String,
+    pub amount_cents: i64,
+    pub interval_months: i32,      // 1 = maandelijks, 12 = jaarlijks
+    pub first_due_on: Option<Date>, // Standaard vandaag
+    pub payee_id: Option<i32>,     // Standaard jijzelf
+}
@@ -3,4 +3,11 @@ pub mod metadata;
 pub mod beers;
 pub mod clubs;
 pub mod chat;
-pub mod events;
\ No newline at end of file
+pub mod events;
+pub mod notifications;
+pub mod e2e;
+pub mod direct_messages;
+pub mod polls;
+pub mod cellar;
+pub mod ledger;
+pub mod stats;
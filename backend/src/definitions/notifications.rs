@@ -0,0 +1,26 @@
+use async_graphql::{SimpleObject, Enum};
+use serde::{Serialize, Deserialize};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "notification_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationKind {
+    Mention,
+    EventUpdated,
+    EventCancelled,
+    EventDeleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, sqlx::FromRow)]
+pub struct Notification {
+    pub id: i32,
+    pub kind: NotificationKind,
+    pub actor_id: Option<i32>,
+    pub actor_display_name: Option<String>,
+    pub club_id: Option<i32>,
+    pub message_id: Option<i32>,
+    pub event_id: Option<i32>,
+    pub detail: Option<String>, // Titel van het event, of de reden bij annulering
+    pub created_at: Option<OffsetDateTime>,
+    pub read_at: Option<OffsetDateTime>,
+}
@@ -0,0 +1,38 @@
+use async_graphql::{SimpleObject, InputObject};
+use serde::{Serialize, Deserialize};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubPoll {
+    pub id: i32,
+    pub club_id: i32,
+    pub created_by: Option<i32>,
+    pub question: String,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<OffsetDateTime>,
+    pub closed_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+    pub is_closed: bool,         // Handmatig gesloten of closes_at verstreken
+    pub options: Vec<PollOption>, // Op volgorde van invoer
+    pub voter_count: i64,        // Aantal mensen dat gestemd heeft, niet het aantal stemmen
+    pub my_option_ids: Vec<i32>, // Waar de ingelogde gebruiker op stemde
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PollOption {
+    pub id: i32,
+    pub label: String,
+    pub vote_count: i64,
+    pub voter_ids: Option<Vec<i32>>, // Null bij anonieme peilingen
+}
+
+#[derive(InputObject)]
+pub struct CreatePollInput {
+    pub club_id: i32,
+    pub question: String,
+    pub options: Vec<String>,
+    pub multiple_choice: Option<bool>, // Standaard één keuze
+    pub anonymous: Option<bool>,
+    pub closes_at: Option<OffsetDateTime>,
+}
@@ -0,0 +1,50 @@
+use async_graphql::SimpleObject;
+use serde::{Serialize, Deserialize};
+use sqlx::types::Uuid;
+use time::OffsetDateTime;
+
+// Alles telt alleen de huidige actieve leden mee.
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubStats {
+    pub club_id: i32,
+    pub most_reviewed_beers: Vec<BeerReviewStat>,
+    pub style_ratings: Vec<StyleRating>,
+    pub attendance_leaders: Vec<MemberStat>, // GOING bij events die al begonnen zijn
+    pub top_chatters: Vec<MemberStat>,       // Berichten in de afgelopen 30 dagen
+    pub new_beers_this_month: Vec<NewClubBeer>,
+    pub generated_at: OffsetDateTime,        // Statistieken worden een paar minuten gecachet
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct BeerReviewStat {
+    pub beer_id: Uuid,
+    pub name: String,
+    pub brewery: Option<String>,
+    pub review_count: i64,
+    pub average_rating: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct StyleRating {
+    pub style: String,
+    pub review_count: i64,
+    pub average_rating: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MemberStat {
+    pub user_id: i32,
+    pub display_name: String,
+    pub count: i64,
+}
+
+/// Een bier dat deze maand voor het eerst door iemand uit de club gereviewd is.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct NewClubBeer {
+    pub beer_id: Uuid,
+    pub name: String,
+    pub brewery: Option<String>,
+    pub first_reviewed_by: i32,
+    pub first_reviewed_at: OffsetDateTime,
+}
@@ -9,11 +9,14 @@ mod definitions;
 mod schema;
 mod utils;
 
-use async_graphql::{EmptySubscription, Schema};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
-use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+use async_graphql::{Data, Schema};
+use async_graphql::dataloader::DataLoader;
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig, ALL_WEBSOCKET_PROTOCOLS};
 use axum::{
-    response::{self, IntoResponse},
+    extract::{Path, WebSocketUpgrade},
+    http::{header, StatusCode},
+    response::{self, IntoResponse, Response},
     routing::{get, post},
     Router,
     Extension,
@@ -22,8 +25,13 @@ use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::schema::{Query, Mutation, BierSchema};
+use crate::schema::{Query, Mutation, Subscription, BierSchema};
+use crate::schema::loaders::{AttachmentLoader, MemberCountLoader, MembershipStatusLoader, MessagePreviewLoader, ReactionLoader, UnreadCountLoader};
 use crate::utils::auth::verify_jwt;
+use crate::utils::{crypto, fs_util, ledger, search_index};
+use crate::utils::pubsub::{self, ChatBroker};
+use crate::utils::presence::PresenceStore;
+use crate::utils::club_stats::ClubStatsCache;
 use sqlx::types::Uuid;
 
 pub struct AuthUser {
@@ -35,6 +43,28 @@ pub struct RequestMetadata {
     pub user_agent: Option<String>,
 }
 
+/// Zelfde check voor HTTP en WebSocket: geldige JWT én een niet-verlopen sessie.
+async fn authenticate(pool: &sqlx::PgPool, auth_str: &str) -> Option<AuthUser> {
+    let token = auth_str.strip_prefix("Bearer ")?;
+    let claims = verify_jwt(token).ok()?;
+    let sid_uuid = Uuid::parse_str(&claims.sid).ok()?;
+
+    sqlx::query!(
+        "SELECT id FROM sessions WHERE id = $1 AND expires_at > NOW()",
+        sid_uuid
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()?;
+
+    let user_id = claims.sub.parse::<i32>().ok()?;
+    Some(AuthUser {
+        id: user_id,
+        session_id: sid_uuid,
+    })
+}
+
 async fn graphql_handler(
     Extension(schema): Extension<BierSchema>,
     Extension(pool): Extension<sqlx::PgPool>,
@@ -48,43 +78,115 @@ async fn graphql_handler(
     };
     req = req.data(metadata);
 
-    if let Some(auth_header) = headers.get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str.starts_with("Bearer ") {
-                let token = &auth_str[7..];
-                if let Ok(claims) = verify_jwt(token) {
-                    if let Ok(sid_uuid) = Uuid::parse_str(&claims.sid) {
-                        let session_exists = sqlx::query!(
-                            "SELECT id FROM sessions WHERE id = $1 AND expires_at > NOW()",
-                            sid_uuid
-                        )
-                        .fetch_optional(&pool)
-                        .await
-                        .ok()
-                        .flatten();
-
-                        if session_exists.is_some() {
-                            if let Ok(user_id) = claims.sub.parse::<i32>() {
-                                req = req.data(AuthUser { 
-                                    id: user_id,
-                                    session_id: sid_uuid,
-                                });
-                            }
-                        }
+    if let Some(auth_str) = headers.get("Authorization").and_then(|h| h.to_str().ok())
+        && let Some(auth_user) = authenticate(&pool, auth_str).await
+    {
+        req = req.data(auth_user);
+    }
+
+    schema.execute(req).await.into()
+}
+
+async fn graphql_ws_handler(
+    Extension(schema): Extension<BierSchema>,
+    Extension(pool): Extension<sqlx::PgPool>,
+    headers: axum::http::HeaderMap,
+    protocol: GraphQLProtocol,
+    websocket: WebSocketUpgrade,
+) -> impl IntoResponse {
+    // Native clients kunnen de header meesturen bij de upgrade, browsers niet:
+    // die sturen het token in de connection_init payload.
+    let header_auth = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .on_connection_init(move |payload| async move {
+                    let auth_str = payload
+                        .get("Authorization")
+                        .or_else(|| payload.get("authorization"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .or(header_auth);
+
+                    let mut data = Data::default();
+                    if let Some(auth_str) = auth_str
+                        && let Some(auth_user) = authenticate(&pool, &auth_str).await
+                    {
+                        data.insert(auth_user);
                     }
-                }
-            }
+                    Ok(data)
+                })
+                .serve()
+        })
+}
+
+/// Bijlagen uit de clubchat, ontsleuteld en alleen voor actieve leden van die club.
+async fn attachment_handler(
+    Extension(pool): Extension<sqlx::PgPool>,
+    headers: axum::http::HeaderMap,
+    Path(attachment_id): Path<Uuid>,
+) -> Response {
+    let Some(auth_str) = headers.get("Authorization").and_then(|h| h.to_str().ok()) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let Some(auth_user) = authenticate(&pool, auth_str).await else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let attachment = sqlx::query!(
+        "SELECT club_id, storage_path, mime_type FROM message_attachments WHERE id = $1",
+        attachment_id
+    )
+    .fetch_optional(&pool)
+    .await;
+
+    let attachment = match attachment {
+        Ok(Some(a)) => a,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Attachment lookup mislukt: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
         }
+    };
+
+    // Niet-leden krijgen 404 zodat ze niet kunnen raden welke bijlagen bestaan
+    if !schema::is_active_member(&pool, attachment.club_id, auth_user.id).await.unwrap_or(false) {
+        return StatusCode::NOT_FOUND.into_response();
     }
 
-    schema.execute(req).await.into()
+    let bytes = match fs_util::load_club_attachment(&attachment.storage_path)
+        .await
+        .and_then(|encrypted| crypto::decrypt_bytes(&encrypted))
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Attachment {} kon niet gelezen worden: {}", attachment_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, attachment.mime_type),
+            (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
 }
 
 async fn graphql_playground() -> impl IntoResponse {
-    response::Html(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+    response::Html(playground_source(GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/graphql/ws")))
 }
 
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
 
 #[tokio::main]
 async fn main() {
@@ -120,8 +222,24 @@ async fn main() {
         .expect("Failed to run database migrations");
     tracing::info!("✅ Migrations executed successfully!");
 
-    let schema = Schema::build(Query, Mutation, EmptySubscription)
+    let broker = ChatBroker::default();
+    let presence = PresenceStore::default();
+    presence.spawn_sweeper();
+    pubsub::spawn_listener(pool.clone(), broker.clone(), presence.clone());
+    search_index::spawn_backfill(pool.clone());
+    ledger::spawn_dues_scheduler(pool.clone());
+
+    let schema = Schema::build(Query, Mutation, Subscription)
         .data(pool.clone())
+        .data(broker)
+        .data(presence)
+        .data(ClubStatsCache::default())
+        .data(DataLoader::new(ReactionLoader { pool: pool.clone() }, tokio::spawn))
+        .data(DataLoader::new(MessagePreviewLoader { pool: pool.clone() }, tokio::spawn))
+        .data(DataLoader::new(UnreadCountLoader { pool: pool.clone() }, tokio::spawn))
+        .data(DataLoader::new(AttachmentLoader { pool: pool.clone() }, tokio::spawn))
+        .data(DataLoader::new(MemberCountLoader { pool: pool.clone() }, tokio::spawn))
+        .data(DataLoader::new(MembershipStatusLoader { pool: pool.clone() }, tokio::spawn))
         .finish();
 
     let cors = CorsLayer::new()
@@ -130,7 +248,13 @@ async fn main() {
         .allow_headers(Any);
 
     let app = Router::new()
-        .route("/graphql", post(graphql_handler))
+        // Ruimte voor één bijlage plus de rest van het multipart request
+        .route(
+            "/graphql",
+            post(graphql_handler).layer(RequestBodyLimitLayer::new(schema::MAX_ATTACHMENT_BYTES + 1024 * 1024)),
+        )
+        .route("/graphql/ws", get(graphql_ws_handler))
+        .route("/attachments/{id}", get(attachment_handler))
         .route("/", get(graphql_playground))
         .layer(Extension(schema))
         .layer(Extension(pool.clone()))
@@ -0,0 +1,242 @@
+use async_graphql::dataloader::Loader;
+use std::collections::HashMap;
+use crate::definitions::chat::{MessageAttachment, MessagePreview, ReactionCount};
+use crate::definitions::clubs::MemberStatus;
+
+/// Haalt reacties voor een hele pagina berichten in één query op.
+/// Sleutel is (message_id, viewer_id) omdat `reactedByMe` per gebruiker verschilt.
+pub struct ReactionLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<(i32, i32)> for ReactionLoader {
+    type Value = Vec<ReactionCount>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[(i32, i32)]) -> Result<HashMap<(i32, i32), Self::Value>, Self::Error> {
+        // In de praktijk is er per batch maar één viewer
+        let mut by_viewer: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (message_id, viewer_id) in keys {
+            by_viewer.entry(*viewer_id).or_default().push(*message_id);
+        }
+
+        let mut result: HashMap<(i32, i32), Self::Value> = HashMap::new();
+
+        for (viewer_id, message_ids) in by_viewer {
+            let rows = sqlx::query!(
+                "SELECT message_id, emoji, COUNT(*) as \"count!\", BOOL_OR(user_id = $2) as \"reacted_by_me!\"
+                 FROM message_reactions
+                 WHERE message_id = ANY($1)
+                 GROUP BY message_id, emoji
+                 ORDER BY MIN(created_at) ASC",
+                &message_ids,
+                viewer_id
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in rows {
+                result.entry((row.message_id, viewer_id)).or_default().push(ReactionCount {
+                    emoji: row.emoji,
+                    count: row.count,
+                    reacted_by_me: row.reacted_by_me,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+// Lengte van het citaat boven een reply
+pub const PREVIEW_CHARS: usize = 100;
+
+/// Parent-berichten voor replies, ontsleuteld en ingekort (E2E berichten blijven ciphertext).
+pub struct MessagePreviewLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<i32> for MessagePreviewLoader {
+    type Value = MessagePreview;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let rows = sqlx::query!(
+            "SELECT cm.id, cm.user_id, cm.content, cm.deleted_at, cm.e2e_key_version, u.display_name as \"user_display_name?\"
+             FROM club_messages cm
+             LEFT JOIN users u ON cm.user_id = u.id
+             WHERE cm.id = ANY($1)",
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let is_deleted = row.deleted_at.is_some();
+                let content = if is_deleted {
+                    String::new()
+                } else if row.e2e_key_version.is_some() {
+                    // Ciphertext kun je niet inkorten; dat doet de client na het ontsleutelen
+                    row.content
+                } else {
+                    crate::utils::crypto::decrypt_string(&row.content)
+                        .map(|text| text.chars().take(PREVIEW_CHARS).collect())
+                        .unwrap_or_default()
+                };
+
+                (row.id, MessagePreview {
+                    id: row.id,
+                    user_id: row.user_id,
+                    user_display_name: row.user_display_name,
+                    content,
+                    is_deleted,
+                    e2e_key_version: row.e2e_key_version,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Ongelezen berichten per club voor een viewer. Sleutel is (club_id, viewer_id).
+/// Eigen en verwijderde berichten tellen niet mee.
+pub struct UnreadCountLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<(i32, i32)> for UnreadCountLoader {
+    type Value = i64;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[(i32, i32)]) -> Result<HashMap<(i32, i32), Self::Value>, Self::Error> {
+        let mut by_viewer: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (club_id, viewer_id) in keys {
+            by_viewer.entry(*viewer_id).or_default().push(*club_id);
+        }
+
+        let mut result = HashMap::new();
+
+        for (viewer_id, club_ids) in by_viewer {
+            let rows = sqlx::query!(
+                "SELECT m.club_id as \"club_id!\", COUNT(cm.id) as \"count!\"
+                 FROM club_memberships m
+                 LEFT JOIN club_messages cm ON cm.club_id = m.club_id
+                    AND cm.deleted_at IS NULL
+                    AND cm.user_id IS DISTINCT FROM m.user_id
+                    AND CASE
+                        WHEN m.last_read_message_id IS NULL THEN cm.created_at > m.joined_at
+                        ELSE cm.id > m.last_read_message_id
+                    END
+                 WHERE m.user_id = $1 AND m.club_id = ANY($2) AND m.status = 'ACTIVE'::member_status
+                 GROUP BY m.club_id",
+                viewer_id,
+                &club_ids
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in rows {
+                result.insert((row.club_id, viewer_id), row.count);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Actieve leden per club.
+pub struct MemberCountLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<i32> for MemberCountLoader {
+    type Value = i64;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let rows = sqlx::query!(
+            "SELECT club_id as \"club_id!\", COUNT(*) as \"count!\"
+             FROM club_memberships
+             WHERE club_id = ANY($1) AND status = 'ACTIVE'::member_status
+             GROUP BY club_id",
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.club_id, row.count)).collect())
+    }
+}
+
+/// Lidmaatschap van de viewer per club. Sleutel is (club_id, viewer_id); geen rij = geen lid.
+pub struct MembershipStatusLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<(i32, i32)> for MembershipStatusLoader {
+    type Value = MemberStatus;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[(i32, i32)]) -> Result<HashMap<(i32, i32), Self::Value>, Self::Error> {
+        let mut by_viewer: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (club_id, viewer_id) in keys {
+            by_viewer.entry(*viewer_id).or_default().push(*club_id);
+        }
+
+        let mut result = HashMap::new();
+
+        for (viewer_id, club_ids) in by_viewer {
+            let rows = sqlx::query!(
+                "SELECT club_id as \"club_id!\", status as \"status!: MemberStatus\"
+                 FROM club_memberships
+                 WHERE user_id = $1 AND club_id = ANY($2)",
+                viewer_id,
+                &club_ids
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in rows {
+                result.insert((row.club_id, viewer_id), row.status);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Bijlagen per bericht, met ontsleutelde bestandsnaam.
+pub struct AttachmentLoader {
+    pub pool: sqlx::PgPool,
+}
+
+impl Loader<i32> for AttachmentLoader {
+    type Value = Vec<MessageAttachment>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let rows = sqlx::query!(
+            "SELECT id, message_id as \"message_id!\", file_name, mime_type, size_bytes
+             FROM message_attachments
+             WHERE message_id = ANY($1)
+             ORDER BY created_at ASC",
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut result: HashMap<i32, Self::Value> = HashMap::new();
+        for row in rows {
+            result.entry(row.message_id).or_default().push(MessageAttachment {
+                id: row.id,
+                file_name: crate::utils::crypto::decrypt_string(&row.file_name).unwrap_or_default(),
+                mime_type: row.mime_type,
+                size_bytes: row.size_bytes,
+                url: format!("/attachments/{}", row.id),
+            });
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::definitions::stats::{BeerReviewStat, ClubStats, MemberStat, NewClubBeer, StyleRating};
+
+// Na een nieuwe review of bericht mogen de statistieken even achterlopen
+pub const STATS_TTL: Duration = Duration::from_secs(5 * 60);
+
+const TOP_LIMIT: i64 = 10;
+const CHAT_WINDOW_DAYS: i32 = 30;
+
+/// Berekende clubStats per club, in geheugen. Vijf aggregaties over alle reviews van de leden
+/// zijn te zwaar om bij elke keer openen van het statistiekenscherm opnieuw te draaien.
+#[derive(Clone, Default)]
+pub struct ClubStatsCache {
+    clubs: Arc<Mutex<HashMap<i32, (Instant, ClubStats)>>>,
+}
+
+impl ClubStatsCache {
+    pub async fn get_or_compute(&self, pool: &sqlx::PgPool, club_id: i32) -> Result<ClubStats, sqlx::Error> {
+        if let Some((computed, stats)) = self.clubs.lock().unwrap().get(&club_id)
+            && computed.elapsed() < STATS_TTL
+        {
+            return Ok(stats.clone());
+        }
+
+        let stats = compute(pool, club_id).await?;
+
+        let mut clubs = self.clubs.lock().unwrap();
+        clubs.retain(|_, (computed, _)| computed.elapsed() < STATS_TTL);
+        clubs.insert(club_id, (Instant::now(), stats.clone()));
+        Ok(stats)
+    }
+}
+
+async fn compute(pool: &sqlx::PgPool, club_id: i32) -> Result<ClubStats, sqlx::Error> {
+    let most_reviewed_beers = sqlx::query_as!(
+        BeerReviewStat,
+        "SELECT b.id as beer_id, b.name, b.brewery,
+                COUNT(*) as \"review_count!\", AVG(r.rating)::float8 as \"average_rating!\"
+         FROM reviews r
+         JOIN club_memberships m ON m.user_id = r.user_id AND m.club_id = $1 AND m.status = 'ACTIVE'::member_status
+         JOIN beers b ON b.id = r.beer_id
+         GROUP BY b.id
+         ORDER BY 4 DESC, 5 DESC, b.name
+         LIMIT $2",
+        club_id,
+        TOP_LIMIT
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Stijl is vrije tekst, dus "IPA" en "ipa " tellen als dezelfde
+    let style_ratings = sqlx::query_as!(
+        StyleRating,
+        "SELECT MIN(TRIM(b.type)) as \"style!\",
+                COUNT(*) as \"review_count!\", AVG(r.rating)::float8 as \"average_rating!\"
+         FROM reviews r
+         JOIN club_memberships m ON m.user_id = r.user_id AND m.club_id = $1 AND m.status = 'ACTIVE'::member_status
+         JOIN beers b ON b.id = r.beer_id
+         WHERE NULLIF(TRIM(b.type), '') IS NOT NULL
+         GROUP BY LOWER(TRIM(b.type))
+         ORDER BY 3 DESC, 2 DESC, 1",
+        club_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let attendance_leaders = sqlx::query_as!(
+        MemberStat,
+        "SELECT u.id as user_id, u.display_name, COUNT(*) as \"count!\"
+         FROM event_attendees a
+         JOIN events e ON e.id = a.event_id AND e.club_id = $1 AND e.starts_at <= NOW() AND e.status = 'SCHEDULED'::event_status
+         JOIN club_memberships m ON m.user_id = a.user_id AND m.club_id = $1 AND m.status = 'ACTIVE'::member_status
+         JOIN users u ON u.id = a.user_id
+         WHERE a.status = 'GOING'::rsvp_status
+         GROUP BY u.id
+         ORDER BY 3 DESC, u.display_name
+         LIMIT $2",
+        club_id,
+        TOP_LIMIT
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let top_chatters = sqlx::query_as!(
+        MemberStat,
+        "SELECT u.id as user_id, u.display_name, COUNT(*) as \"count!\"
+         FROM club_messages cm
+         JOIN club_memberships m ON m.user_id = cm.user_id AND m.club_id = $1 AND m.status = 'ACTIVE'::member_status
+         JOIN users u ON u.id = cm.user_id
+         WHERE cm.club_id = $1 AND cm.deleted_at IS NULL
+           AND cm.created_at > NOW() - make_interval(days => $3)
+         GROUP BY u.id
+         ORDER BY 3 DESC, u.display_name
+         LIMIT $2",
+        club_id,
+        TOP_LIMIT,
+        CHAT_WINDOW_DAYS
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Eerste review per bier binnen de club; alleen tonen als die in deze kalendermaand valt
+    let new_beers_this_month = sqlx::query_as!(
+        NewClubBeer,
+        "SELECT f.beer_id as \"beer_id!\", b.name, b.brewery,
+                f.user_id as \"first_reviewed_by!\", f.created_at as \"first_reviewed_at!\"
+         FROM (
+            SELECT DISTINCT ON (r.beer_id) r.beer_id, r.user_id, r.created_at
+            FROM reviews r
+            JOIN club_memberships m ON m.user_id = r.user_id AND m.club_id = $1 AND m.status = 'ACTIVE'::member_status
+            ORDER BY r.beer_id, r.created_at
+         ) f
+         JOIN beers b ON b.id = f.beer_id
+         WHERE f.created_at >= date_trunc('month', NOW())
+         ORDER BY f.created_at DESC",
+        club_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ClubStats {
+        club_id,
+        most_reviewed_beers,
+        style_ratings,
+        attendance_leaders,
+        top_chatters,
+        new_beers_this_month,
+        generated_at: time::OffsetDateTime::now_utc(),
+    })
+}
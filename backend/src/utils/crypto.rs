@@ -106,3 +106,53 @@ pub fn decrypt_metadata(encrypted_hex: &str) -> Result<Metadata, String> {
     
     serde_json::from_slice(&decrypted_bytes).map_err(|e| format!("Metadata parsing failed: {}", e))
 }
+
+/// Zelfde AES-GCM als encrypt_string, maar voor binaire bestanden: nonce + ciphertext als ruwe bytes.
+pub fn encrypt_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    let key_hex = std::env::var("ENCRYPTION_KEY").map_err(|_| "ENCRYPTION_KEY not set")?;
+    let key_bytes = hex::decode(key_hex).map_err(|_| "Invalid hex in ENCRYPTION_KEY")?;
+    
+    let cipher = Aes256Gcm::new_from_slice(&key_bytes).map_err(|e| format!("Invalid key: {}", e))?;
+    
+    let mut nonce_bytes = [0u8; 12];
+    CryptoOsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes); 
+    
+    let encrypted_bytes = cipher.encrypt(nonce, data)
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+    
+    let mut final_payload = nonce_bytes.to_vec();
+    final_payload.extend_from_slice(&encrypted_bytes);
+    
+    Ok(final_payload)
+}
+
+pub fn decrypt_bytes(payload: &[u8]) -> Result<Vec<u8>, String> {
+    if payload.len() < 12 {
+        return Err("Invalid payload".into());
+    }
+
+    let (nonce_bytes, encrypted_bytes) = payload.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let key_hex = std::env::var("ENCRYPTION_KEY").map_err(|_| "ENCRYPTION_KEY not set")?;
+    let key_bytes = hex::decode(key_hex).map_err(|_| "Invalid hex in ENCRYPTION_KEY")?;
+    
+    let cipher = Aes256Gcm::new_from_slice(&key_bytes).map_err(|e| format!("Invalid key: {}", e))?;
+    
+    cipher.decrypt(nonce, encrypted_bytes)
+        .map_err(|e| format!("Decryption failed: {}", e))
+}
+
+// Zonder 0/O en 1/I, zodat een code ook over te typen is. 32 tekens: geen modulo bias.
+const INVITE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Willekeurige uitnodigingscode voor een club, bijv. "K7QX2MZP9D".
+pub fn generate_invite_code() -> String {
+    let mut bytes = [0u8; 10];
+    CryptoOsRng.fill_bytes(&mut bytes);
+    bytes
+        .iter()
+        .map(|b| INVITE_ALPHABET[(*b as usize) % INVITE_ALPHABET.len()] as char)
+        .collect()
+}
@@ -36,3 +36,58 @@ pub async fn load_encrypted_metadata(user_id: i32) -> Result<String, String> {
         format!("Failed to read metadata file: {}", e)
     })
 }
+
+pub async fn save_club_attachment(club_id: i32, attachment_id: &str, encrypted: &[u8]) -> Result<String, String> {
+    let dir = format!("../assets/club_data/{}/attachments", club_id);
+    fs::create_dir_all(&dir).await.map_err(|e| {
+        tracing::error!("Kon attachment directory niet maken: {}", e);
+        format!("Filesystem error: {}", e)
+    })?;
+
+    let path = format!("{}/{}.bin", dir, attachment_id);
+    fs::write(&path, encrypted).await.map_err(|e| {
+        format!("Failed to write attachment: {}", e)
+    })?;
+    Ok(path)
+}
+
+pub async fn load_club_attachment(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).await.map_err(|e| {
+        format!("Failed to read attachment: {}", e)
+    })
+}
+
+pub async fn remove_files(paths: &[String]) {
+    for path in paths {
+        if let Err(e) = fs::remove_file(path).await {
+            tracing::warn!("Kon bestand {} niet verwijderen: {}", path, e);
+        }
+    }
+}
+
+/// Alles onder ../assets/club_data/{club_id}, voor als de club verwijderd wordt.
+pub async fn remove_club_directory(club_id: i32) {
+    let dir = format!("../assets/club_data/{}", club_id);
+    match fs::remove_dir_all(&dir).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("Kon club directory {} niet verwijderen: {}", dir, e),
+    }
+}
+
+/// Bepaalt het bestandstype op basis van de eerste bytes, niet op wat de client zegt.
+pub fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+const DUES_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// 2^16 deelverzamelingen zijn een paar milliseconden; elk extra lid verdubbelt dat
+const EXACT_TRANSFER_LIMIT: usize = 16;
+
+/// Verdeelt `amount_cents` gelijk. De centen die overblijven gaan één voor één naar de laagste user ids,
+/// zodat de som altijd klopt en dezelfde invoer dezelfde verdeling geeft.
+pub fn split_evenly(amount_cents: i64, participants: &[i32]) -> Vec<(i32, i64)> {
+    let mut participants = participants.to_vec();
+    participants.sort_unstable();
+    participants.dedup();
+    if participants.is_empty() {
+        return Vec::new();
+    }
+
+    let count = participants.len() as i64;
+    let base = amount_cents / count;
+    let remainder = amount_cents % count;
+
+    participants
+        .into_iter()
+        .enumerate()
+        .map(|(i, user_id)| (user_id, base + if (i as i64) < remainder { 1 } else { 0 }))
+        .collect()
+}
+
+/// Het kleinste aantal overboekingen dat alle saldi op nul zet. Dat is n min het grootste aantal
+/// groepen waarin de saldi op te delen zijn die elk samen op nul uitkomen; binnen zo'n groep van k
+/// leden volstaan k - 1 overboekingen. Boven `EXACT_TRANSFER_LIMIT` open saldi is het zoeken naar
+/// die groepen te duur en rekenen we de hele club in één keer af (hoogstens n - 1 overboekingen).
+pub fn minimal_transfers(balances: &[(i32, i64)]) -> Vec<(i32, i32, i64)> {
+    let open: Vec<(i32, i64)> = balances.iter().copied().filter(|(_, b)| *b != 0).collect();
+    if open.len() > EXACT_TRANSFER_LIMIT {
+        return settle_group(&open);
+    }
+
+    zero_sum_groups(&open)
+        .iter()
+        .flat_map(|group| settle_group(group))
+        .collect()
+}
+
+/// Deelt de saldi op in zoveel mogelijk groepen die elk op nul uitkomen.
+fn zero_sum_groups(balances: &[(i32, i64)]) -> Vec<Vec<(i32, i64)>> {
+    let n = balances.len();
+    let full = (1usize << n) - 1;
+
+    // sum[mask]: som van de saldi in mask; best[mask]: max aantal nul-groepen binnen mask
+    let mut sum = vec![0i64; 1 << n];
+    let mut best = vec![0u32; 1 << n];
+    for mask in 1..=full {
+        let lowest = mask.trailing_zeros() as usize;
+        sum[mask] = sum[mask & (mask - 1)] + balances[lowest].1;
+        let without_one = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| best[mask ^ (1 << i)])
+            .max()
+            .unwrap_or(0);
+        best[mask] = without_one + u32::from(sum[mask] == 0);
+    }
+
+    // Terug van de volledige set naar leeg; tussen twee masks met som nul zit precies één groep
+    let mut groups = Vec::new();
+    let mut group = Vec::new();
+    let mut mask = full;
+    while mask != 0 {
+        let bonus = u32::from(sum[mask] == 0);
+        let i = (0..n)
+            .find(|i| mask & (1 << i) != 0 && best[mask ^ (1 << i)] + bonus == best[mask])
+            .unwrap_or(mask.trailing_zeros() as usize);
+        group.push(balances[i]);
+        mask ^= 1 << i;
+        if sum[mask] == 0 {
+            groups.push(std::mem::take(&mut group));
+        }
+    }
+    groups
+}
+
+/// Rekent een groep die samen op nul uitkomt af: steeds de grootste schuldenaar naar de grootste
+/// schuldeiser. Elke overboeking zet minstens één lid op nul, de laatste twee, dus hoogstens k - 1.
+fn settle_group(balances: &[(i32, i64)]) -> Vec<(i32, i32, i64)> {
+    let mut creditors: Vec<(i32, i64)> = balances.iter().copied().filter(|(_, b)| *b > 0).collect();
+    let mut debtors: Vec<(i32, i64)> = balances.iter().map(|(id, b)| (*id, -b)).filter(|(_, b)| *b > 0).collect();
+
+    let mut transfers = Vec::new();
+    loop {
+        // Grootste eerst; bij gelijke bedragen op user id voor een stabiele uitkomst
+        creditors.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        debtors.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let (Some(creditor), Some(debtor)) = (creditors.first_mut(), debtors.first_mut()) else {
+            break;
+        };
+        let amount = creditor.1.min(debtor.1);
+        transfers.push((debtor.0, creditor.0, amount));
+        creditor.1 -= amount;
+        debtor.1 -= amount;
+
+        creditors.retain(|(_, b)| *b > 0);
+        debtors.retain(|(_, b)| *b > 0);
+    }
+    transfers
+}
+
+/// Boekt elk uur de contributie die vervallen is.
+pub fn spawn_dues_scheduler(pool: sqlx::PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DUES_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match charge_due_dues(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Contributie: {} periodes geboekt", count),
+                Err(e) => tracing::error!("Contributie boeken mislukt: {}", e),
+            }
+        }
+    });
+}
+
+/// Eén boeking per vervallen periode; een server die een tijd uit stond haalt de gemiste periodes in.
+async fn charge_due_dues(pool: &sqlx::PgPool) -> Result<usize, sqlx::Error> {
+    let mut charged = 0;
+
+    loop {
+        let mut tx = pool.begin().await?;
+
+        let Some(dues) = sqlx::query!(
+            "SELECT d.id, d.club_id, d.payee_id, d.description, d.amount_cents, d.interval_months, d.next_due_on,
+                    c.archived_at IS NOT NULL as \"archived!\"
+             FROM club_dues d
+             JOIN clubs c ON c.id = d.club_id
+             WHERE d.active AND d.next_due_on <= CURRENT_DATE
+             ORDER BY d.next_due_on
+             LIMIT 1
+             FOR UPDATE OF d SKIP LOCKED"
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            break;
+        };
+
+        // Gearchiveerde clubs betalen niets, maar de periode schuift wel door
+        if !dues.archived {
+            let members = sqlx::query_scalar!(
+                "SELECT user_id FROM club_memberships
+                 WHERE club_id = $1 AND status = 'ACTIVE'::member_status AND user_id <> $2",
+                dues.club_id,
+                dues.payee_id
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            if !members.is_empty() {
+                let expense_id = sqlx::query_scalar!(
+                    "INSERT INTO club_expenses (club_id, paid_by, description, amount_cents, dues_id, dues_period)
+                     VALUES ($1, $2, $3, $4, $5, $6)
+                     ON CONFLICT (dues_id, dues_period) DO NOTHING
+                     RETURNING id",
+                    dues.club_id,
+                    dues.payee_id,
+                    dues.description,
+                    dues.amount_cents * members.len() as i64,
+                    dues.id,
+                    dues.next_due_on
+                )
+                .fetch_optional(&mut *tx)
+                .await?;
+
+                if let Some(expense_id) = expense_id {
+                    sqlx::query!(
+                        "INSERT INTO club_expense_shares (expense_id, user_id, share_cents)
+                         SELECT $1, UNNEST($2::int[]), $3",
+                        expense_id,
+                        &members,
+                        dues.amount_cents
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+                    charged += 1;
+                }
+            }
+        }
+
+        // Vanaf de eerste termijn rekenen, zodat de 31e niet blijvend naar de 28e zakt
+        sqlx::query!(
+            "UPDATE club_dues
+             SET periods_charged = periods_charged + 1,
+                 next_due_on = (first_due_on + make_interval(months => interval_months * (periods_charged + 1)))::date
+             WHERE id = $1",
+            dues.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    Ok(charged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // Voert de overboekingen uit en controleert dat iedereen daarna op nul staat
+    fn assert_settles(balances: &[(i32, i64)], transfers: &[(i32, i32, i64)]) {
+        let mut left: HashMap<i32, i64> = balances.iter().copied().collect();
+        for &(from, to, amount) in transfers {
+            assert!(amount > 0, "overboeking van {} cent", amount);
+            assert_ne!(from, to);
+            *left.get_mut(&from).expect("onbekende betaler") += amount;
+            *left.get_mut(&to).expect("onbekende ontvanger") -= amount;
+        }
+        assert!(left.values().all(|b| *b == 0), "niet iedereen staat op nul: {:?}", left);
+    }
+
+    #[test]
+    fn all_zero_needs_no_transfers() {
+        assert!(minimal_transfers(&[]).is_empty());
+        assert!(minimal_transfers(&[(1, 0), (2, 0), (3, 0)]).is_empty());
+    }
+
+    #[test]
+    fn single_pair() {
+        assert_eq!(minimal_transfers(&[(1, 500), (2, -500)]), vec![(2, 1, 500)]);
+    }
+
+    #[test]
+    fn independent_pairs_settle_separately() {
+        let balances = [(1, 1000), (2, 300), (3, -300), (4, -1000)];
+        let transfers = minimal_transfers(&balances);
+        assert_settles(&balances, &transfers);
+        assert_eq!(transfers.len(), 2);
+    }
+
+    #[test]
+    fn finds_groups_that_greedy_misses() {
+        // {1, 2} en {3, 4, 5} komen elk op nul: 1 + 2 overboekingen. Alles in één keer afrekenen kost er 4.
+        let balances = [(1, 3), (2, -3), (3, 2), (4, 2), (5, -4)];
+        assert_eq!(settle_group(&balances).len(), 4);
+
+        let transfers = minimal_transfers(&balances);
+        assert_settles(&balances, &transfers);
+        assert_eq!(transfers.len(), 3);
+    }
+
+    #[test]
+    fn many_balances_fall_back_to_one_group() {
+        // Boven EXACT_TRANSFER_LIMIT: geen zoektocht naar groepen, wel hoogstens n - 1 overboekingen
+        let mut balances: Vec<(i32, i64)> = (1..=20).map(|id| (id, id as i64 * 100)).collect();
+        let total: i64 = balances.iter().map(|(_, b)| b).sum();
+        balances.push((21, -total / 2));
+        balances.push((22, -(total - total / 2)));
+        assert!(balances.len() > EXACT_TRANSFER_LIMIT);
+
+        let transfers = minimal_transfers(&balances);
+        assert_settles(&balances, &transfers);
+        assert!(transfers.len() < balances.len());
+    }
+
+    #[test]
+    fn remainder_cents_go_to_lowest_ids() {
+        assert_eq!(split_evenly(100, &[3, 1, 2]), vec![(1, 34), (2, 33), (3, 33)]);
+        assert_eq!(split_evenly(101, &[7, 5, 9]), vec![(5, 34), (7, 34), (9, 33)]);
+    }
+
+    #[test]
+    fn split_ignores_duplicates_and_handles_nobody() {
+        assert_eq!(split_evenly(100, &[2, 1, 2]), vec![(1, 50), (2, 50)]);
+        assert!(split_evenly(100, &[]).is_empty());
+    }
+}
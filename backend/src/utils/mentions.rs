@@ -0,0 +1,87 @@
+/// Zoekt "@Naam" mentions van clubleden in een bericht en geeft hun user ids terug.
+/// Namen mogen spaties bevatten, dus we matchen tegen de bekende namen in plaats van te tokenizen.
+pub fn find_mentions(content: &str, members: &[(i32, String)]) -> Vec<i32> {
+    let mut mentioned = Vec::new();
+
+    for (at, _) in content.match_indices('@') {
+        // "jan@bier.nl" is een e-mailadres, geen mention
+        if content[..at].chars().next_back().is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+
+        let rest = &content[at + 1..];
+        // Langste naam wint, zodat "@Jan de Vries" niet als "@Jan" telt
+        let best = members
+            .iter()
+            .filter(|(_, name)| !name.is_empty() && starts_with_name(rest, name))
+            .max_by_key(|(_, name)| name.chars().count());
+
+        if let Some((user_id, _)) = best
+            && !mentioned.contains(user_id)
+        {
+            mentioned.push(*user_id);
+        }
+    }
+
+    mentioned
+}
+
+fn starts_with_name(text: &str, name: &str) -> bool {
+    let mut text_chars = text.chars();
+    for name_char in name.chars() {
+        match text_chars.next() {
+            Some(c) if c.to_lowercase().eq(name_char.to_lowercase()) => {}
+            _ => return false,
+        }
+    }
+    // Naam moet op een woordgrens eindigen: "@Janneke" is geen mention van Jan
+    !text_chars.next().is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members() -> Vec<(i32, String)> {
+        vec![
+            (1, "Jan".to_string()),
+            (2, "Jan Willem".to_string()),
+            (3, "Ærø".to_string()),
+            (4, String::new()),
+        ]
+    }
+
+    #[test]
+    fn longest_name_wins() {
+        assert_eq!(find_mentions("Hoi @Jan Willem!", &members()), vec![2]);
+        assert_eq!(find_mentions("Hoi @Jan Wil", &members()), vec![1]);
+        assert_eq!(find_mentions("@Jan en @Jan Willem", &members()), vec![1, 2]);
+    }
+
+    #[test]
+    fn skips_email_addresses() {
+        assert!(find_mentions("mail jan@example.com", &members()).is_empty());
+        assert!(find_mentions("mail me op piet@jan", &members()).is_empty());
+    }
+
+    #[test]
+    fn name_ends_on_word_boundary() {
+        assert_eq!(find_mentions("@Jan, kom je?", &members()), vec![1]);
+        assert_eq!(find_mentions("(@Jan)", &members()), vec![1]);
+        assert_eq!(find_mentions("Proost @Jan.", &members()), vec![1]);
+        assert!(find_mentions("@Janneke", &members()).is_empty());
+        assert!(find_mentions("@Jan_", &members()).is_empty());
+    }
+
+    #[test]
+    fn ignores_case_and_duplicates() {
+        assert_eq!(find_mentions("@jan @JAN @jAn wILLEM", &members()), vec![1, 2]);
+        assert_eq!(find_mentions("@ærø", &members()), vec![3]);
+    }
+
+    #[test]
+    fn lone_at_sign_mentions_nobody() {
+        assert!(find_mentions("@", &members()).is_empty());
+        assert!(find_mentions("@ iedereen", &members()).is_empty());
+    }
+}
@@ -3,3 +3,11 @@ pub mod fs_util;
 pub mod auth;
 pub mod email;
 pub mod sanitization;
+pub mod pubsub;
+pub mod pagination;
+pub mod presence;
+pub mod mentions;
+pub mod text;
+pub mod search_index;
+pub mod ledger;
+pub mod club_stats;
@@ -0,0 +1,54 @@
+use base64::{engine::general_purpose, Engine as _};
+use time::OffsetDateTime;
+
+/// Keyset cursor op (created_at, id). Voor de client is het een ondoorzichtige string.
+pub fn encode_cursor(created_at: OffsetDateTime, id: i32) -> String {
+    let raw = format!("{}:{}", created_at.unix_timestamp_nanos(), id);
+    general_purpose::URL_SAFE_NO_PAD.encode(raw)
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<(OffsetDateTime, i32), String> {
+    let bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| "Ongeldige cursor")?;
+    let raw = String::from_utf8(bytes).map_err(|_| "Ongeldige cursor")?;
+
+    let (nanos, id) = raw.split_once(':').ok_or("Ongeldige cursor")?;
+    let nanos = nanos.parse::<i128>().map_err(|_| "Ongeldige cursor")?;
+    let id = id.parse::<i32>().map_err(|_| "Ongeldige cursor")?;
+    let created_at = OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| "Ongeldige cursor")?;
+
+    Ok((created_at, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let created_at = OffsetDateTime::from_unix_timestamp_nanos(1_760_000_000_123_456_789).unwrap();
+        let cursor = encode_cursor(created_at, 42);
+        assert_eq!(decode_cursor(&cursor), Ok((created_at, 42)));
+    }
+
+    #[test]
+    fn cursor_roundtrip_before_epoch() {
+        let created_at = OffsetDateTime::from_unix_timestamp_nanos(-1_500).unwrap();
+        assert_eq!(decode_cursor(&encode_cursor(created_at, 1)), Ok((created_at, 1)));
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        let encode = |raw: &str| general_purpose::URL_SAFE_NO_PAD.encode(raw);
+
+        assert!(decode_cursor("").is_err());
+        assert!(decode_cursor("niet base64!").is_err());
+        assert!(decode_cursor(&general_purpose::URL_SAFE_NO_PAD.encode([0xff, 0xfe])).is_err());
+        assert!(decode_cursor(&encode("123")).is_err());
+        assert!(decode_cursor(&encode("abc:1")).is_err());
+        assert!(decode_cursor(&encode("123:abc")).is_err());
+        assert!(decode_cursor(&encode("123:99999999999")).is_err());
+        assert!(decode_cursor(&encode(&format!("{}:1", i128::MAX))).is_err());
+    }
+}
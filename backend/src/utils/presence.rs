@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+// Client stuurt elke ~30s een heartbeat; twee gemiste = offline
+pub const ONLINE_TTL: Duration = Duration::from_secs(65);
+// Client stuurt setTyping hooguit elke paar seconden zolang er getypt wordt
+pub const TYPING_TTL: Duration = Duration::from_secs(6);
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct ClubPresence {
+    online: HashMap<i32, Instant>,
+    typing: HashMap<i32, Instant>,
+}
+
+impl ClubPresence {
+    // Geeft true terug als er iets verlopen is
+    fn prune(&mut self, now: Instant) -> bool {
+        let before = self.online.len() + self.typing.len();
+        self.online.retain(|_, seen| now.duration_since(*seen) < ONLINE_TTL);
+        self.typing.retain(|_, seen| now.duration_since(*seen) < TYPING_TTL);
+        before != self.online.len() + self.typing.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.online.is_empty() && self.typing.is_empty()
+    }
+}
+
+/// Wie er online is en wie er typt, per club. Bewust alleen in geheugen:
+/// verliezen bij een herstart is prima, en het kost geen database writes per toetsaanslag.
+#[derive(Clone)]
+pub struct PresenceStore {
+    clubs: Arc<Mutex<HashMap<i32, ClubPresence>>>,
+    // Stuurt het club_id waarvan de presence veranderd is
+    changes: broadcast::Sender<i32>,
+}
+
+impl Default for PresenceStore {
+    fn default() -> Self {
+        let (changes, _) = broadcast::channel(256);
+        Self {
+            clubs: Arc::new(Mutex::new(HashMap::new())),
+            changes,
+        }
+    }
+}
+
+impl PresenceStore {
+    pub fn heartbeat(&self, club_id: i32, user_id: i32) {
+        let is_new = {
+            let mut clubs = self.clubs.lock().unwrap();
+            let club = clubs.entry(club_id).or_default();
+            club.online.insert(user_id, Instant::now()).is_none()
+        };
+        if is_new {
+            let _ = self.changes.send(club_id);
+        }
+    }
+
+    pub fn set_typing(&self, club_id: i32, user_id: i32, typing: bool) {
+        let changed = {
+            let mut clubs = self.clubs.lock().unwrap();
+            let club = clubs.entry(club_id).or_default();
+            // Wie typt is ook online
+            club.online.insert(user_id, Instant::now());
+            if typing {
+                club.typing.insert(user_id, Instant::now()).is_none()
+            } else {
+                club.typing.remove(&user_id).is_some()
+            }
+        };
+        if changed {
+            let _ = self.changes.send(club_id);
+        }
+    }
+
+    /// (online, typing) user ids, zonder verlopen entries.
+    pub fn snapshot(&self, club_id: i32) -> (Vec<i32>, Vec<i32>) {
+        let mut clubs = self.clubs.lock().unwrap();
+        let Some(club) = clubs.get_mut(&club_id) else {
+            return (Vec::new(), Vec::new());
+        };
+        club.prune(Instant::now());
+
+        let mut online: Vec<i32> = club.online.keys().copied().collect();
+        let mut typing: Vec<i32> = club.typing.keys().copied().collect();
+        online.sort_unstable();
+        typing.sort_unstable();
+        (online, typing)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<i32> {
+        self.changes.subscribe()
+    }
+
+    /// Ruimt periodiek verlopen entries op, zodat subscribers ook horen dat iemand gestopt is met typen.
+    pub fn spawn_sweeper(&self) {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let changed: Vec<i32> = {
+                    let now = Instant::now();
+                    let mut clubs = store.clubs.lock().unwrap();
+                    let changed = clubs
+                        .iter_mut()
+                        .filter_map(|(club_id, club)| club.prune(now).then_some(*club_id))
+                        .collect();
+                    clubs.retain(|_, club| !club.is_empty());
+                    changed
+                };
+                for club_id in changed {
+                    let _ = store.changes.send(club_id);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Serialize, Deserialize};
+use sqlx::postgres::PgListener;
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::definitions::chat::ClubMessage;
+use crate::utils::presence::PresenceStore;
+
+// Hoeveel berichten een trage subscriber achter mag lopen voordat hij berichten mist
+const CHANNEL_CAPACITY: usize = 256;
+
+// Postgres kanaal waarop elke backend-instantie luistert
+pub const NOTIFY_CHANNEL: &str = "bier_realtime";
+
+const RECONNECT_DELAY_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub enum ChatEvent {
+    MessageAdded(ClubMessage),
+    MessageUpdated(ClubMessage), // Bewerkt of verwijderd (tombstone)
+    MembershipEnded { club_id: i32, user_id: i32 },
+}
+
+impl ChatEvent {
+    /// Of deze gebruiker vanaf nu niks meer uit de club mag ontvangen.
+    pub fn ends_membership_of(&self, club_id: i32, user_id: i32) -> bool {
+        matches!(self, ChatEvent::MembershipEnded { club_id: c, user_id: u } if *c == club_id && *u == user_id)
+    }
+}
+
+/// In-process fan-out van chat events naar open GraphQL subscriptions.
+#[derive(Clone)]
+pub struct ChatBroker {
+    sender: broadcast::Sender<ChatEvent>,
+}
+
+impl Default for ChatBroker {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl ChatBroker {
+    pub fn publish(&self, event: ChatEvent) {
+        // Err betekent alleen dat er op dit moment niemand luistert
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChatEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Payload van een NOTIFY. Alleen ids: de inhoud staat versleuteld in de database
+/// en NOTIFY payloads zijn beperkt tot 8000 bytes.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RealtimeEvent {
+    MessageAdded { message_id: i32 },
+    MessageUpdated { message_id: i32 },
+    // Presence gaat ook via NOTIFY zodat elke instantie hetzelfde beeld heeft, zonder tabel-writes
+    Heartbeat { club_id: i32, user_id: i32 },
+    Typing { club_id: i32, user_id: i32, typing: bool },
+    // Verwijderd, geband of club weg: open subscriptions van dit lid stoppen
+    MembershipEnded { club_id: i32, user_id: i32 },
+}
+
+/// Stuurt een event naar alle instanties. Binnen een transactie wordt de NOTIFY
+/// pas afgeleverd na de commit, dus luisteraars zien nooit een half-opgeslagen rij.
+pub async fn notify<'e, E>(executor: E, event: &RealtimeEvent) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let payload = serde_json::to_string(event).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(NOTIFY_CHANNEL)
+        .bind(payload)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Start de achtergrondtaak die NOTIFY events ophaalt en naar de lokale subscribers stuurt.
+pub fn spawn_listener(pool: sqlx::PgPool, broker: ChatBroker, presence: PresenceStore) {
+    tokio::spawn(async move {
+        let mut delay = RECONNECT_DELAY_MIN;
+
+        loop {
+            let Err(e) = listen(&pool, &broker, &presence, &mut delay).await;
+            tracing::error!("Realtime listener gestopt: {}. Opnieuw verbinden over {:?}", e, delay);
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(RECONNECT_DELAY_MAX);
+        }
+    });
+}
+
+/// Stopt alleen met een fout. Zodra LISTEN gelukt is zetten we `delay` terug, zodat een verbinding
+/// die uren goed ging na een storing niet op de wachttijd van een eerdere storing blijft hangen.
+async fn listen(
+    pool: &sqlx::PgPool,
+    broker: &ChatBroker,
+    presence: &PresenceStore,
+    delay: &mut Duration,
+) -> Result<Infallible, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(NOTIFY_CHANNEL).await?;
+    *delay = RECONNECT_DELAY_MIN;
+    tracing::info!("Luistert naar realtime events op kanaal {}", NOTIFY_CHANNEL);
+
+    loop {
+        // try_recv geeft None als de verbinding wegviel; de volgende aanroep verbindt opnieuw.
+        // Events uit dat gat zijn verloren, clients halen die op via clubMessages.
+        let Some(notification) = listener.try_recv().await? else {
+            tracing::warn!("Realtime verbinding met Postgres verbroken, opnieuw verbinden...");
+            continue;
+        };
+
+        match serde_json::from_str::<RealtimeEvent>(notification.payload()) {
+            Ok(event) => {
+                if let Err(e) = handle_event(pool, broker, presence, event).await {
+                    tracing::error!("Realtime event kon niet verwerkt worden: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Onbekende realtime payload {:?}: {}", notification.payload(), e),
+        }
+    }
+}
+
+async fn handle_event(
+    pool: &sqlx::PgPool,
+    broker: &ChatBroker,
+    presence: &PresenceStore,
+    event: RealtimeEvent,
+) -> Result<(), sqlx::Error> {
+    match event {
+        RealtimeEvent::MessageAdded { message_id } => {
+            if let Some(message) = crate::schema::load_club_message(pool, message_id).await? {
+                // Wie verstuurt is klaar met typen
+                if let Some(user_id) = message.user_id {
+                    presence.set_typing(message.club_id, user_id, false);
+                }
+                broker.publish(ChatEvent::MessageAdded(message));
+            }
+        }
+        RealtimeEvent::MessageUpdated { message_id } => {
+            if let Some(message) = crate::schema::load_club_message(pool, message_id).await? {
+                broker.publish(ChatEvent::MessageUpdated(message));
+            }
+        }
+        RealtimeEvent::Heartbeat { club_id, user_id } => presence.heartbeat(club_id, user_id),
+        RealtimeEvent::Typing { club_id, user_id, typing } => presence.set_typing(club_id, user_id, typing),
+        RealtimeEvent::MembershipEnded { club_id, user_id } => {
+            broker.publish(ChatEvent::MembershipEnded { club_id, user_id });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn membership_ended_survives_notify_payload() {
+        let payload = serde_json::to_string(&RealtimeEvent::MembershipEnded { club_id: 7, user_id: 42 }).unwrap();
+        let event = serde_json::from_str::<RealtimeEvent>(&payload).unwrap();
+        assert!(matches!(event, RealtimeEvent::MembershipEnded { club_id: 7, user_id: 42 }));
+    }
+
+    #[test]
+    fn membership_ended_only_concerns_that_member() {
+        let event = ChatEvent::MembershipEnded { club_id: 7, user_id: 42 };
+        assert!(event.ends_membership_of(7, 42));
+        assert!(!event.ends_membership_of(7, 43));
+        assert!(!event.ends_membership_of(8, 42));
+    }
+}
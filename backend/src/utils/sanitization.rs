@@ -20,13 +20,11 @@ pub fn sanitize_email(email: &str) -> String {
                 // We assume the verified TLD is part of the domain.
                 
                 // Let's verify this is the domain part
-                if let Some(at_idx) = email.find('@') {
-                    if idx > at_idx {
-                         match email.get(0..end_of_tld) {
-                             Some(clean_slice) => return clean_slice.to_string(),
-                             None => {} // Should not happen
-                         }
-                    }
+                if let Some(at_idx) = email.find('@')
+                    && idx > at_idx
+                    && let Some(clean_slice) = email.get(0..end_of_tld)
+                {
+                    return clean_slice.to_string();
                 }
             }
         }
@@ -39,3 +37,112 @@ pub fn sanitize_email(email: &str) -> String {
 
     email
 }
+
+/// Eén emoji (of emoji-sequentie) voor reacties. Gewone tekst zoals "+1", "é" of "ok👍" weigeren we,
+/// net als meerdere emoji achter elkaar ("🍺🍺"): die zouden elk als aparte reactie tellen.
+pub fn is_valid_emoji(emoji: &str) -> bool {
+    if emoji.is_empty() || emoji.len() > 32 {
+        return false;
+    }
+
+    let chars: Vec<char> = emoji.chars().collect();
+    let mut has_pictograph = false;
+    let mut regional_run = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        // Eerst: huidskleuren liggen ook in het pictogram-bereik
+        if is_emoji_modifier(c) {
+            regional_run = 0;
+            continue;
+        }
+
+        if matches!(c, '0'..='9' | '#' | '*') {
+            // Keycap: cijfer, # of * gevolgd door (optioneel FE0F en) U+20E3
+            let next = chars[i + 1..].iter().find(|&&n| n != '\u{FE0F}');
+            if next != Some(&'\u{20E3}') {
+                return false;
+            }
+        } else if !is_emoji_pictograph(c) {
+            return false;
+        }
+
+        // Een tweede emoji mag alleen via ZWJ, of als tweede helft van een vlag (twee regional indicators)
+        let regional = is_regional_indicator(c);
+        let joined = (i > 0 && chars[i - 1] == '\u{200D}') || (regional && regional_run % 2 == 1);
+        if has_pictograph && !joined {
+            return false;
+        }
+        has_pictograph = true;
+        regional_run = if regional { regional_run + 1 } else { 0 };
+    }
+    has_pictograph
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+// Codepoints die zelf als emoji getekend worden
+fn is_emoji_pictograph(c: char) -> bool {
+    matches!(c as u32,
+        0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x24C2
+        | 0x2194..=0x21AA
+        | 0x2300..=0x23FF
+        | 0x25AA..=0x25FE
+        | 0x2600..=0x27BF
+        | 0x2934 | 0x2935
+        | 0x2B05..=0x2B55
+        | 0x3030 | 0x303D | 0x3297 | 0x3299
+        | 0x1F000..=0x1FAFF // O.a. vlaggen (regional indicators), smileys, dieren, eten
+    )
+}
+
+// Onderdelen van een sequentie: ZWJ, variation selectors, huidskleur, keycap en tag-tekens (subvlaggen)
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0x200D | 0xFE0E | 0xFE0F | 0x20E3 | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_single_emoji() {
+        for emoji in ["🍺", "👍", "❤️", "☕", "©️"] {
+            assert!(is_valid_emoji(emoji), "{} geweigerd", emoji);
+        }
+    }
+
+    #[test]
+    fn accepts_sequences() {
+        for emoji in [
+            "👍🏽", // huidskleur
+            "👨‍👩‍👧‍👦", // ZWJ familie
+            "🏳️‍🌈", // ZWJ met variation selector
+            "1️⃣", // keycap
+            "#⃣", // keycap zonder FE0F
+            "🇳🇱", // vlag
+            "🏴󠁧󠁢󠁳󠁣󠁴󠁿", // subvlag met tag-tekens
+        ] {
+            assert!(is_valid_emoji(emoji), "{:?} geweigerd", emoji);
+        }
+    }
+
+    #[test]
+    fn rejects_text() {
+        for text in [
+            "", "+1", "é", "a", "1", "#", "ok👍", "👍 ", "🍺!", "\u{200D}", "\u{FE0F}",
+            // Meer dan één emoji
+            "🍺🍺", "🍺🍺🍺🍺", "👍👍🏽", "🇳🇱🇧🇪", "🇳🇱🇧", "1️⃣2️⃣", "🍺1️⃣",
+        ] {
+            assert!(!is_valid_emoji(text), "{:?} geaccepteerd", text);
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_input() {
+        // Eén ZWJ-sequentie, maar langer dan 32 bytes
+        let long = ["🍺"; 9].join("\u{200D}");
+        assert!(long.len() > 32);
+        assert!(!is_valid_emoji(&long));
+    }
+}
@@ -0,0 +1,154 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::utils::text::normalize_words;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// SEARCH_INDEX_KEY als bytes, alleen als er ook echt een HMAC mee te maken is.
+pub fn index_key() -> Result<Vec<u8>, String> {
+    let key_hex = std::env::var("SEARCH_INDEX_KEY").map_err(|_| "SEARCH_INDEX_KEY not set")?;
+    let key_bytes = hex::decode(key_hex).map_err(|_| "Invalid hex in SEARCH_INDEX_KEY")?;
+    HmacSha256::new_from_slice(&key_bytes).map_err(|e| format!("Invalid key: {}", e))?;
+    Ok(key_bytes)
+}
+
+/// Blind index tokens voor een tekst binnen een club. Het club_id zit in de HMAC,
+/// zodat hetzelfde woord in verschillende clubs niet aan elkaar te koppelen is.
+pub fn tokens_for(club_id: i32, text: &str) -> Result<Vec<Vec<u8>>, String> {
+    tokens_with_key(&index_key()?, club_id, text)
+}
+
+fn tokens_with_key(key_bytes: &[u8], club_id: i32, text: &str) -> Result<Vec<Vec<u8>>, String> {
+    normalize_words(text)
+        .iter()
+        .map(|word| {
+            let mut mac = HmacSha256::new_from_slice(key_bytes).map_err(|e| format!("Invalid key: {}", e))?;
+            mac.update(club_id.to_string().as_bytes());
+            mac.update(b":");
+            mac.update(word.as_bytes());
+            Ok(mac.finalize().into_bytes().to_vec())
+        })
+        .collect()
+}
+
+/// Vervangt de tokens van een bericht. Lege tekst (bijv. een tombstone) haalt alles weg.
+/// Zonder geldige SEARCH_INDEX_KEY slaan we het over en geven we `false` terug;
+/// het bericht blijft dan op `search_indexed = FALSE` staan.
+pub async fn index_message(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    message_id: i32,
+    club_id: i32,
+    text: &str,
+) -> Result<bool, sqlx::Error> {
+    let tokens = match tokens_for(club_id, text) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            tracing::warn!("Bericht {} niet geïndexeerd: {}", message_id, e);
+            return Ok(false);
+        }
+    };
+
+    sqlx::query!("DELETE FROM message_search_tokens WHERE message_id = $1", message_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query!(
+        "INSERT INTO message_search_tokens (message_id, club_id, token)
+         SELECT $1, $2, unnest($3::bytea[])
+         ON CONFLICT DO NOTHING",
+        message_id,
+        club_id,
+        &tokens
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!("UPDATE club_messages SET search_indexed = TRUE WHERE id = $1", message_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(true)
+}
+
+const BACKFILL_BATCH: i64 = 500;
+
+/// Indexeert berichten die nog geen tokens hebben (van vóór de zoekfunctie).
+pub fn spawn_backfill(pool: sqlx::PgPool) {
+    tokio::spawn(async move {
+        // Eén keer vooraf controleren; met een ongeldige sleutel zou elke batch dezelfde rijen teruggeven
+        if let Err(e) = index_key() {
+            tracing::warn!("Zoeken in chat is uitgeschakeld: {}", e);
+            return;
+        }
+
+        loop {
+            match backfill_batch(&pool).await {
+                Ok(0) => break,
+                Ok(count) => tracing::info!("Zoekindex: {} berichten geïndexeerd", count),
+                Err(e) => {
+                    tracing::error!("Zoekindex backfill gestopt: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+async fn backfill_batch(pool: &sqlx::PgPool) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT id, club_id as \"club_id!\", content, deleted_at FROM club_messages
+         WHERE NOT search_indexed
+         ORDER BY id
+         LIMIT $1",
+        BACKFILL_BATCH
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    for row in &rows {
+        // Onleesbare of verwijderde berichten krijgen geen tokens, maar tellen wel als gedaan
+        let text = match row.deleted_at {
+            Some(_) => String::new(),
+            None => crate::utils::crypto::decrypt_string(&row.content).unwrap_or_default(),
+        };
+        if !index_message(&mut tx, row.id, row.club_id, &text).await? {
+            // Niets gemarkeerd, dus de volgende batch zou precies hetzelfde zijn
+            return Ok(0);
+        }
+    }
+    tx.commit().await?;
+
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-sleutel-voor-de-blind-index";
+
+    #[test]
+    fn query_matches_indexed_text_regardless_of_case_and_accents() {
+        let indexed = tokens_with_key(KEY, 1, "Café BRUIN, morgen?").unwrap();
+        for query in ["cafe", "CAFÉ", "bruin", "Bruin café"] {
+            let tokens = tokens_with_key(KEY, 1, query).unwrap();
+            assert!(!tokens.is_empty());
+            assert!(tokens.iter().all(|token| indexed.contains(token)), "{} niet gevonden", query);
+        }
+    }
+
+    #[test]
+    fn same_word_differs_per_club() {
+        let club_one = tokens_with_key(KEY, 1, "bruin").unwrap();
+        let club_two = tokens_with_key(KEY, 2, "bruin").unwrap();
+        assert_eq!(club_one.len(), 1);
+        assert_ne!(club_one, club_two);
+    }
+
+    #[test]
+    fn same_word_differs_per_key() {
+        let other = tokens_with_key(b"een-andere-sleutel", 1, "bruin").unwrap();
+        assert_ne!(tokens_with_key(KEY, 1, "bruin").unwrap(), other);
+    }
+}
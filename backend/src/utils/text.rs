@@ -0,0 +1,107 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// "Café Ærø" -> "Cafe Ærø": accenten eraf via NFKD, de rest blijft staan.
+pub fn fold_diacritics(input: &str) -> String {
+    input.nfkd().filter(|c| !is_combining_mark(*c)).collect()
+}
+
+/// Losse woorden in kleine letters zonder accenten, voor zoeken.
+pub fn normalize_words(input: &str) -> Vec<String> {
+    let folded = fold_diacritics(input).to_lowercase();
+    let mut words: Vec<String> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(|w| w.to_string())
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// "Bier & Co Ærø!" -> "bier-co-aero". Alleen a-z, 0-9 en enkele streepjes, max 60 tekens.
+pub fn slugify(input: &str) -> String {
+    let mut slug = String::new();
+    for c in fold_diacritics(input).to_lowercase().chars() {
+        // Letters die NFKD niet opsplitst
+        let mapped = match c {
+            'ß' => "ss",
+            'æ' => "ae",
+            'œ' => "oe",
+            'ø' => "o",
+            'đ' | 'ð' => "d",
+            'ł' => "l",
+            'þ' => "th",
+            'ı' => "i",
+            c if c.is_ascii_alphanumeric() => {
+                slug.push(c);
+                continue;
+            }
+            _ => "-",
+        };
+        if mapped == "-" && (slug.is_empty() || slug.ends_with('-')) {
+            continue;
+        }
+        slug.push_str(mapped);
+    }
+
+    let mut slug: String = slug.chars().take(MAX_SLUG_LEN).collect();
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    if slug.is_empty() {
+        slug.push_str("club");
+    }
+    slug
+}
+
+const MAX_SLUG_LEN: usize = 60;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_words_folds_case_and_accents() {
+        assert_eq!(normalize_words("Café BRUIN"), vec!["bruin", "cafe"]);
+        // Zoekopdracht en bericht moeten op dezelfde woorden uitkomen
+        assert_eq!(normalize_words("bruin cafe"), normalize_words("Café BRUIN"));
+        assert_eq!(normalize_words("CAFÉ"), normalize_words("café"));
+        // é als e + losse accent (zoals sommige toetsenborden het sturen)
+        assert_eq!(normalize_words("Cafe\u{301}"), vec!["cafe"]);
+    }
+
+    #[test]
+    fn normalize_words_splits_on_punctuation_and_drops_short_words() {
+        assert_eq!(normalize_words("Proost! Bier-proeverij, 8 uur: a ok"), vec!["bier", "ok", "proeverij", "proost", "uur"]);
+        assert_eq!(normalize_words("bier BIER Bier"), vec!["bier"]);
+        assert!(normalize_words("!? a").is_empty());
+    }
+
+    #[test]
+    fn slugify_collapses_punctuation() {
+        assert_eq!(slugify("Bier & Co"), "bier-co");
+        assert_eq!(slugify("  --De Gouden Tap!--  "), "de-gouden-tap");
+        assert_eq!(slugify("IPA's 4 life"), "ipa-s-4-life");
+    }
+
+    #[test]
+    fn slugify_folds_accents_and_special_letters() {
+        assert_eq!(slugify("Café Ærø"), "cafe-aero");
+        assert_eq!(slugify("Brauhaus Straße"), "brauhaus-strasse");
+        assert_eq!(slugify("Łódź Øl"), "lodz-ol");
+    }
+
+    #[test]
+    fn slugify_caps_length_without_trailing_dash() {
+        assert_eq!(slugify(&"a".repeat(80)), "a".repeat(60));
+        // Het 60e teken is een streepje en valt weg
+        assert_eq!(slugify(&format!("{} bier", "a".repeat(59))), "a".repeat(59));
+    }
+
+    #[test]
+    fn slugify_falls_back_to_club() {
+        assert_eq!(slugify(""), "club");
+        assert_eq!(slugify("!!!"), "club");
+        assert_eq!(slugify("🍺🍺"), "club");
+    }
+}
@@ -0,0 +1,125 @@
+// Clubs vinden en lid worden.
+use serde::{Deserialize, Serialize};
+
+use crate::api::post;
+
+pub const PAGE_SIZE: i32 = 20;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClubSort {
+    Newest,
+    MostMembers,
+    MostActive,
+}
+
+impl ClubSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            ClubSort::Newest => "Nieuwste",
+            ClubSort::MostMembers => "Meeste leden",
+            ClubSort::MostActive => "Meest actief",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ClubSummary {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    #[serde(rename = "imageUrl")]
+    pub image_url: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(rename = "memberCount")]
+    pub member_count: i64,
+    pub visibility: String, // PUBLIC, REQUEST of INVITE_ONLY
+    #[serde(rename = "myStatus")]
+    pub my_status: Option<String>, // ACTIVE, PENDING of BANNED; null als je geen lid bent
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ClubPage {
+    pub clubs: Vec<ClubSummary>,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    #[serde(rename = "clubCount")]
+    pub club_count: i64,
+}
+
+#[derive(Serialize)]
+struct ClubsVariables<'a> {
+    search: Option<&'a str>,
+    tag: Option<&'a str>,
+    sort: ClubSort,
+    limit: i32,
+    offset: i32,
+}
+
+#[derive(Deserialize)]
+struct ClubsData {
+    clubs: ClubPage,
+}
+
+#[derive(Deserialize)]
+struct TagsData {
+    #[serde(rename = "popularClubTags")]
+    popular_club_tags: Vec<TagCount>,
+}
+
+#[derive(Serialize)]
+struct JoinVariables {
+    #[serde(rename = "clubId")]
+    club_id: i32,
+}
+
+#[derive(Deserialize)]
+struct JoinData {
+    #[serde(rename = "joinClub")]
+    join_club: bool,
+}
+
+pub async fn search_clubs(
+    token: &str,
+    search: &str,
+    tag: Option<&str>,
+    sort: ClubSort,
+    offset: i32,
+) -> Result<ClubPage, String> {
+    let search = search.trim();
+    let data: ClubsData = post(
+        token,
+        "query($search: String, $tag: String, $sort: ClubSort, $limit: Int, $offset: Int) { clubs(search: $search, tag: $tag, sort: $sort, limit: $limit, offset: $offset) { hasMore clubs { id name slug description imageUrl tags memberCount visibility myStatus } } }",
+        ClubsVariables {
+            search: (!search.is_empty()).then_some(search),
+            tag,
+            sort,
+            limit: PAGE_SIZE,
+            offset,
+        },
+    )
+    .await?;
+    Ok(data.clubs)
+}
+
+pub async fn popular_tags(token: &str) -> Result<Vec<TagCount>, String> {
+    let data: TagsData = post(token, "query { popularClubTags(limit: 12) { tag clubCount } }", ()).await?;
+    Ok(data.popular_club_tags)
+}
+
+/// Lid worden, of bij een REQUEST club een verzoek sturen dat een beheerder nog moet goedkeuren.
+pub async fn join_club(token: &str, club_id: i32) -> Result<bool, String> {
+    let data: JoinData = post(
+        token,
+        "mutation($clubId: Int!) { joinClub(clubId: $clubId) }",
+        JoinVariables { club_id },
+    )
+    .await?;
+    Ok(data.join_club)
+}
@@ -0,0 +1,432 @@
+// End-to-end versleuteling voor de clubchat.
+//
+// Elke gebruiker heeft een X25519 sleutelpaar; de private key blijft op het toestel.
+// Een club heeft per versie één symmetrische clubsleutel (AES-256-GCM) die voor elk lid
+// apart wordt ingepakt met diens publieke sleutel. De server ziet alleen enveloppen en ciphertext.
+//
+// Er is nog geen chatscherm: alleen de login gebruikt deze module (publish_public_key). De functies
+// met `allow(dead_code)` zijn voor dat scherm, inclusief reageren op `e2eRotationNeeded`.
+
+use std::collections::HashMap;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng, Payload, rand_core::RngCore},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::api::{post, storage};
+
+pub type ClubKey = [u8; 32];
+
+const NONCE_LEN: usize = 12;
+const WRAP_INFO: &[u8] = b"bier-club-key-wrap";
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Sleutelpaar van de ingelogde gebruiker.
+pub struct Identity {
+    secret: StaticSecret,
+}
+
+impl Identity {
+    /// Laadt de private key van dit toestel, of maakt er een aan bij het eerste gebruik.
+    /// Een nieuwe sleutel die niet opgeslagen kan worden is een fout: publiceren zou je enveloppen wissen.
+    pub fn load_or_create(user_id: i32) -> Result<Self, String> {
+        let stored = storage::load_e2e_secret(user_id)
+            .and_then(|b64| STANDARD.decode(b64.trim()).ok())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+
+        let secret = match stored {
+            Some(bytes) => StaticSecret::from(bytes),
+            None => {
+                let bytes: [u8; 32] = random_bytes();
+                storage::save_e2e_secret(user_id, &STANDARD.encode(bytes))?;
+                StaticSecret::from(bytes)
+            }
+        };
+
+        Ok(Self { secret })
+    }
+
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(PublicKey::from(&self.secret).as_bytes())
+    }
+}
+
+pub fn generate_club_key() -> ClubKey {
+    random_bytes()
+}
+
+// Sleutel voor één envelop: ECDH tussen een wegwerp-sleutel en de ontvanger, door HKDF gehaald
+fn wrapping_cipher(shared: &[u8; 32], ephemeral: &PublicKey, recipient: &PublicKey) -> Aes256Gcm {
+    let mut salt = Vec::with_capacity(64);
+    salt.extend_from_slice(ephemeral.as_bytes());
+    salt.extend_from_slice(recipient.as_bytes());
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(WRAP_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF output length");
+    Aes256Gcm::new(&key.into())
+}
+
+/// Pakt een clubsleutel in voor één ontvanger. Formaat: base64(ephemeral_pub | nonce | ciphertext).
+pub fn wrap_club_key(club_key: &ClubKey, recipient_public_key: &str) -> Result<String, String> {
+    let recipient_bytes: [u8; 32] = STANDARD
+        .decode(recipient_public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Ongeldige publieke sleutel")?;
+    let recipient = PublicKey::from(recipient_bytes);
+
+    let ephemeral_secret = StaticSecret::from(random_bytes::<32>());
+    let ephemeral = PublicKey::from(&ephemeral_secret);
+    let shared = ephemeral_secret.diffie_hellman(&recipient);
+
+    let nonce: [u8; NONCE_LEN] = random_bytes();
+    let ciphertext = wrapping_cipher(shared.as_bytes(), &ephemeral, &recipient)
+        .encrypt(Nonce::from_slice(&nonce), club_key.as_slice())
+        .map_err(|_| "Sleutel inpakken mislukt")?;
+
+    let mut payload = ephemeral.as_bytes().to_vec();
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(payload))
+}
+
+pub fn unwrap_club_key(identity: &Identity, wrapped_key: &str) -> Result<ClubKey, String> {
+    let payload = STANDARD.decode(wrapped_key).map_err(|_| "Ongeldige envelop")?;
+    if payload.len() < 32 + NONCE_LEN {
+        return Err("Ongeldige envelop".to_string());
+    }
+    let (ephemeral_bytes, rest) = payload.split_at(32);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let ephemeral = PublicKey::from(<[u8; 32]>::try_from(ephemeral_bytes).map_err(|_| "Ongeldige envelop")?);
+    let recipient = PublicKey::from(&identity.secret);
+    let shared = identity.secret.diffie_hellman(&ephemeral);
+
+    let key = wrapping_cipher(shared.as_bytes(), &ephemeral, &recipient)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Deze sleutel is niet voor jou ingepakt")?;
+
+    key.try_into().map_err(|_| "Ongeldige clubsleutel".to_string())
+}
+
+// Club en versie als associated data: ciphertext uit een andere club of versie faalt
+fn message_aad(club_id: i32, key_version: i32) -> Vec<u8> {
+    format!("bier-club:{}:v{}", club_id, key_version).into_bytes()
+}
+
+/// Versleutelt een chatbericht. Formaat: base64(nonce | ciphertext).
+pub fn encrypt_message(club_key: &ClubKey, club_id: i32, key_version: i32, plaintext: &str) -> Result<String, String> {
+    let nonce: [u8; NONCE_LEN] = random_bytes();
+    let aad = message_aad(club_id, key_version);
+    let ciphertext = Aes256Gcm::new(club_key.into())
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: &aad })
+        .map_err(|_| "Versleutelen mislukt")?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(payload))
+}
+
+pub fn decrypt_message(club_key: &ClubKey, club_id: i32, key_version: i32, ciphertext: &str) -> Result<String, String> {
+    let payload = STANDARD.decode(ciphertext).map_err(|_| "Ongeldig bericht")?;
+    if payload.len() < NONCE_LEN {
+        return Err("Ongeldig bericht".to_string());
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let aad = message_aad(club_id, key_version);
+    let plaintext = Aes256Gcm::new(club_key.into())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+        .map_err(|_| "Bericht kon niet ontsleuteld worden")?;
+
+    String::from_utf8(plaintext).map_err(|_| "Bericht kon niet ontsleuteld worden".to_string())
+}
+
+/// Alle clubsleutels van één club die dit toestel kan openen, per versie.
+#[derive(Default)]
+pub struct ClubKeyring {
+    pub club_id: i32,
+    keys: HashMap<i32, ClubKey>,
+}
+
+impl ClubKeyring {
+    pub fn current(&self) -> Option<(i32, &ClubKey)> {
+        self.keys.iter().max_by_key(|(version, _)| **version).map(|(version, key)| (*version, key))
+    }
+
+    #[allow(dead_code)]
+    pub fn encrypt(&self, plaintext: &str) -> Result<(i32, String), String> {
+        let (version, key) = self.current().ok_or("Je hebt de clubsleutel nog niet ontvangen")?;
+        Ok((version, encrypt_message(key, self.club_id, version, plaintext)?))
+    }
+
+    /// Voor het tonen van een bericht: `None` als we de sleutel van die versie (nog) niet hebben.
+    #[allow(dead_code)]
+    pub fn decrypt(&self, key_version: i32, ciphertext: &str) -> Option<String> {
+        let key = self.keys.get(&key_version)?;
+        decrypt_message(key, self.club_id, key_version, ciphertext).ok()
+    }
+}
+
+#[derive(Serialize)]
+struct ClubVariables {
+    #[serde(rename = "clubId")]
+    club_id: i32,
+}
+
+#[derive(Serialize)]
+struct EnvelopeInput {
+    #[serde(rename = "userId")]
+    user_id: i32,
+    #[serde(rename = "wrappedKey")]
+    wrapped_key: String,
+}
+
+#[derive(Serialize)]
+struct EnvelopesVariables {
+    #[serde(rename = "clubId")]
+    club_id: i32,
+    #[serde(rename = "keyVersion", skip_serializing_if = "Option::is_none")]
+    key_version: Option<i32>,
+    envelopes: Vec<EnvelopeInput>,
+}
+
+#[derive(Deserialize)]
+struct MemberKey {
+    #[serde(rename = "userId")]
+    user_id: i32,
+    #[serde(rename = "publicKey")]
+    public_key: Option<String>,
+    #[serde(rename = "hasCurrentKey")]
+    has_current_key: bool,
+}
+
+#[derive(Deserialize)]
+struct MemberKeysData {
+    #[serde(rename = "clubMemberKeys")]
+    club_member_keys: Vec<MemberKey>,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(rename = "keyVersion")]
+    key_version: i32,
+    #[serde(rename = "wrappedKey")]
+    wrapped_key: String,
+}
+
+#[derive(Deserialize)]
+struct MyKeysData {
+    #[serde(rename = "myClubKeys")]
+    my_club_keys: Vec<Envelope>,
+}
+
+/// Publiceer de publieke sleutel van dit toestel. Doe dit na elke login; dezelfde sleutel opnieuw sturen is gratis.
+pub async fn publish_public_key(token: &str, identity: &Identity) -> Result<(), String> {
+    #[derive(Serialize)]
+    struct Variables {
+        #[serde(rename = "publicKey")]
+        public_key: String,
+    }
+
+    post::<_, serde_json::Value>(
+        token,
+        "mutation($publicKey: String!) { publishPublicKey(publicKey: $publicKey) }",
+        Variables { public_key: identity.public_key() },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Haalt alle enveloppen van de club op en opent ze. Enveloppen voor een oude private key worden overgeslagen.
+pub async fn load_keyring(token: &str, identity: &Identity, club_id: i32) -> Result<ClubKeyring, String> {
+    let data: MyKeysData = post(
+        token,
+        "query($clubId: Int!) { myClubKeys(clubId: $clubId) { keyVersion wrappedKey } }",
+        ClubVariables { club_id },
+    )
+    .await?;
+
+    let keys = data
+        .my_club_keys
+        .into_iter()
+        .filter_map(|envelope| {
+            unwrap_club_key(identity, &envelope.wrapped_key).ok().map(|key| (envelope.key_version, key))
+        })
+        .collect();
+
+    Ok(ClubKeyring { club_id, keys })
+}
+
+async fn member_keys(token: &str, club_id: i32) -> Result<Vec<MemberKey>, String> {
+    let data: MemberKeysData = post(
+        token,
+        "query($clubId: Int!) { clubMemberKeys(clubId: $clubId) { userId publicKey hasCurrentKey } }",
+        ClubVariables { club_id },
+    )
+    .await?;
+    Ok(data.club_member_keys)
+}
+
+// Pakt een sleutel in voor alle leden met een publieke sleutel die aan `include` voldoen
+fn wrap_for(club_key: &ClubKey, members: &[MemberKey], include: impl Fn(&MemberKey) -> bool) -> Vec<EnvelopeInput> {
+    members
+        .iter()
+        .filter(|member| include(member))
+        .filter_map(|member| {
+            let public_key = member.public_key.as_deref()?;
+            let wrapped_key = wrap_club_key(club_key, public_key).ok()?;
+            Some(EnvelopeInput { user_id: member.user_id, wrapped_key })
+        })
+        .collect()
+}
+
+/// Zet E2E aan voor een club (alleen OWNER/MOD) en geeft de eerste sleutel terug.
+#[allow(dead_code)]
+pub async fn enable_club_e2e(token: &str, identity: &Identity, club_id: i32) -> Result<ClubKeyring, String> {
+    let members = member_keys(token, club_id).await?;
+    let club_key = generate_club_key();
+
+    post::<_, serde_json::Value>(
+        token,
+        "mutation($clubId: Int!, $envelopes: [KeyEnvelopeInput!]!) { enableClubE2e(clubId: $clubId, envelopes: $envelopes) { id } }",
+        EnvelopesVariables { club_id, key_version: None, envelopes: wrap_for(&club_key, &members, |_| true) },
+    )
+    .await?;
+
+    load_keyring(token, identity, club_id).await
+}
+
+/// Deelt de huidige sleutel met leden die hem nog missen, zoals nieuwe leden. Geeft het aantal terug.
+#[allow(dead_code)]
+pub async fn share_with_pending_members(token: &str, keyring: &ClubKeyring) -> Result<i32, String> {
+    let Some((key_version, club_key)) = keyring.current() else {
+        return Ok(0);
+    };
+    let members = member_keys(token, keyring.club_id).await?;
+    let envelopes = wrap_for(club_key, &members, |member| !member.has_current_key);
+    if envelopes.is_empty() {
+        return Ok(0);
+    }
+
+    #[derive(Deserialize)]
+    struct ShareData {
+        #[serde(rename = "shareClubKey")]
+        share_club_key: i32,
+    }
+
+    let data: ShareData = post(
+        token,
+        "mutation($clubId: Int!, $keyVersion: Int!, $envelopes: [KeyEnvelopeInput!]!) { shareClubKey(clubId: $clubId, keyVersion: $keyVersion, envelopes: $envelopes) }",
+        EnvelopesVariables { club_id: keyring.club_id, key_version: Some(key_version), envelopes },
+    )
+    .await?;
+    Ok(data.share_club_key)
+}
+
+/// Nieuwe clubsleutel voor de huidige leden. Nodig zodra de club `e2eRotationNeeded` meldt.
+#[allow(dead_code)]
+pub async fn rotate_club_key(token: &str, identity: &Identity, keyring: &ClubKeyring) -> Result<ClubKeyring, String> {
+    let (current_version, _) = keyring.current().ok_or("Je hebt de clubsleutel nog niet ontvangen")?;
+    let members = member_keys(token, keyring.club_id).await?;
+    let club_key = generate_club_key();
+
+    post::<_, serde_json::Value>(
+        token,
+        "mutation($clubId: Int!, $keyVersion: Int!, $envelopes: [KeyEnvelopeInput!]!) { rotateClubKey(clubId: $clubId, keyVersion: $keyVersion, envelopes: $envelopes) { id } }",
+        EnvelopesVariables {
+            club_id: keyring.club_id,
+            key_version: Some(current_version + 1),
+            envelopes: wrap_for(&club_key, &members, |_| true),
+        },
+    )
+    .await?;
+
+    load_keyring(token, identity, keyring.club_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity() -> Identity {
+        Identity { secret: StaticSecret::from(random_bytes::<32>()) }
+    }
+
+    #[test]
+    fn wrapped_club_key_opens_for_recipient_only() {
+        let alice = identity();
+        let bob = identity();
+        let club_key = generate_club_key();
+
+        let wrapped = wrap_club_key(&club_key, &alice.public_key()).unwrap();
+        assert_eq!(unwrap_club_key(&alice, &wrapped).unwrap(), club_key);
+        assert!(unwrap_club_key(&bob, &wrapped).is_err());
+    }
+
+    #[test]
+    fn wrap_rejects_invalid_public_key() {
+        let club_key = generate_club_key();
+        assert!(wrap_club_key(&club_key, "geen base64!").is_err());
+        assert!(wrap_club_key(&club_key, &STANDARD.encode([0u8; 16])).is_err());
+    }
+
+    #[test]
+    fn unwrap_rejects_malformed_envelope() {
+        let alice = identity();
+        assert!(unwrap_club_key(&alice, "geen base64!").is_err());
+        assert!(unwrap_club_key(&alice, &STANDARD.encode([0u8; 20])).is_err());
+    }
+
+    #[test]
+    fn message_roundtrip() {
+        let club_key = generate_club_key();
+        let ciphertext = encrypt_message(&club_key, 7, 1, "Proost! 🍺").unwrap();
+        assert_ne!(ciphertext, "Proost! 🍺");
+        assert_eq!(decrypt_message(&club_key, 7, 1, &ciphertext).unwrap(), "Proost! 🍺");
+    }
+
+    #[test]
+    fn message_is_bound_to_club_version_and_key() {
+        let club_key = generate_club_key();
+        let ciphertext = encrypt_message(&club_key, 7, 1, "Proost!").unwrap();
+
+        assert!(decrypt_message(&club_key, 8, 1, &ciphertext).is_err());
+        assert!(decrypt_message(&club_key, 7, 2, &ciphertext).is_err());
+        assert!(decrypt_message(&generate_club_key(), 7, 1, &ciphertext).is_err());
+        assert!(decrypt_message(&club_key, 7, 1, "geen base64!").is_err());
+    }
+
+    #[test]
+    fn keyring_encrypts_with_newest_version() {
+        let old_key = generate_club_key();
+        let new_key = generate_club_key();
+        let keyring = ClubKeyring { club_id: 7, keys: HashMap::from([(1, old_key), (2, new_key)]) };
+
+        let (version, ciphertext) = keyring.encrypt("Proost!").unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(keyring.decrypt(2, &ciphertext).as_deref(), Some("Proost!"));
+
+        let old = encrypt_message(&old_key, 7, 1, "Oud bericht").unwrap();
+        assert_eq!(keyring.decrypt(1, &old).as_deref(), Some("Oud bericht"));
+        assert_eq!(keyring.decrypt(3, &old), None);
+    }
+
+    #[test]
+    fn empty_keyring_cannot_encrypt() {
+        let keyring = ClubKeyring { club_id: 7, ..Default::default() };
+        assert!(keyring.encrypt("Proost!").is_err());
+    }
+}
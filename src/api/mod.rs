@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use config::API_URL;
 
 #[derive(Serialize)]
 pub struct GraphQLRequest<V: Serialize> {
@@ -6,5 +8,41 @@ pub struct GraphQLRequest<V: Serialize> {
     pub variables: V,
 }
 
+#[derive(Deserialize)]
+struct GraphQLResponse<D> {
+    data: Option<D>,
+    errors: Option<Vec<GraphQLError>>,
+}
+
+#[derive(Deserialize)]
+struct GraphQLError {
+    message: String,
+}
+
+/// GraphQL request met het token van de ingelogde gebruiker. Fouten zijn meteen toonbaar.
+pub async fn post<V: Serialize, D: DeserializeOwned>(token: &str, query: &'static str, variables: V) -> Result<D, String> {
+    let client = reqwest::Client::new();
+    let request = GraphQLRequest { query, variables };
+
+    let body = client
+        .post(API_URL)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&request)
+        .send()
+        .await
+        .map_err(|_| "Check je internetverbinding even, we kunnen de server niet bereiken.".to_string())?
+        .json::<GraphQLResponse<D>>()
+        .await
+        .map_err(|_| "Er is een probleem met de gegevens. Onze fout!".to_string())?;
+
+    match (body.data, body.errors) {
+        (_, Some(errors)) if !errors.is_empty() => Err(errors[0].message.clone()),
+        (Some(data), _) => Ok(data),
+        _ => Err("Er is een probleem met de gegevens. Onze fout!".to_string()),
+    }
+}
+
+pub mod clubs;
 pub mod config;
+pub mod e2e;
 pub mod storage;
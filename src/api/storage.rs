@@ -1,9 +1,10 @@
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 use crate::AuthState;
 use directories::ProjectDirs;
 
-fn get_storage_path() -> Option<PathBuf> {
+fn get_data_dir() -> Option<PathBuf> {
     // Gebruik package qualifier "com", organization "bier", application "app"
     // Op Android zal dit waarschijnlijk mappen naar app-specifieke storage.
     if let Some(proj_dirs) = ProjectDirs::from("com", "bier", "app") {
@@ -11,11 +12,20 @@ fn get_storage_path() -> Option<PathBuf> {
         if !data_dir.exists() {
             let _ = fs::create_dir_all(data_dir);
         }
-        return Some(data_dir.join("session.json"));
+        return Some(data_dir.to_path_buf());
     }
     None
 }
 
+fn get_storage_path() -> Option<PathBuf> {
+    get_data_dir().map(|dir| dir.join("session.json"))
+}
+
+// Private E2E sleutel per gebruiker. Blijft staan na uitloggen, anders ben je je chatgeschiedenis kwijt.
+fn get_e2e_key_path(user_id: i32) -> Option<PathBuf> {
+    get_data_dir().map(|dir| dir.join(format!("e2e_{}.key", user_id)))
+}
+
 pub fn save_auth_state(state: &AuthState) {
     if let Some(path) = get_storage_path() {
         if let Ok(json) = serde_json::to_string(state) {
@@ -40,3 +50,33 @@ pub fn clear_auth_state() {
         let _ = fs::remove_file(path);
     }
 }
+
+/// Alleen leesbaar voor de eigenaar. Een fout moet de aanroeper zien: met een sleutel die niet bewaard
+/// is zou elke login een nieuwe publiceren en ben je al je clubsleutels kwijt.
+pub fn save_e2e_secret(user_id: i32, secret: &str) -> Result<(), String> {
+    let path = get_e2e_key_path(user_id).ok_or("Geen opslagmap voor je sleutel gevonden")?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&path).map_err(|e| format!("Sleutel opslaan mislukt: {}", e))?;
+    // `mode` geldt alleen voor een nieuw bestand; een bestaand bestand zetten we alsnog dicht
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Sleutel opslaan mislukt: {}", e))?;
+    }
+    file.write_all(secret.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Sleutel opslaan mislukt: {}", e))
+}
+
+pub fn load_e2e_secret(user_id: i32) -> Option<String> {
+    get_e2e_key_path(user_id).and_then(|path| fs::read_to_string(path).ok())
+}
@@ -0,0 +1,180 @@
+use dioxus::prelude::*;
+pub mod view_model;
+use view_model::ClubsViewModel;
+use crate::api::clubs::ClubSort;
+use crate::components::inputs::GlassInput;
+use crate::AuthState;
+
+const SORTS: [ClubSort; 3] = [ClubSort::Newest, ClubSort::MostMembers, ClubSort::MostActive];
+
+#[component]
+pub fn ClubsScreen() -> Element {
+    let vm = use_signal(ClubsViewModel::new);
+    let auth = use_context::<Signal<AuthState>>();
+
+    use_effect(move || {
+        spawn(async move {
+            vm().load_tags(auth).await;
+            vm().reload(auth).await;
+        });
+    });
+
+    rsx! {
+        div {
+            class: "glass",
+            style: "margin: 20px; padding: 20px; display: flex; flex-direction: column; gap: 16px;",
+            h1 { "Clubs 🛡️" }
+
+            div {
+                style: "display: flex; gap: 8px; align-items: center;",
+                GlassInput {
+                    placeholder: "Zoek op naam of beschrijving",
+                    value: (vm().search)(),
+                    oninput: move |evt: FormEvent| {
+                        let mut search = vm().search;
+                        search.set(evt.value());
+                    }
+                }
+                button {
+                    class: "glass-btn",
+                    style: "width: auto; padding: 0 16px;",
+                    onclick: move |_| {
+                        spawn(async move {
+                            vm().reload(auth).await;
+                        });
+                    },
+                    "🔍"
+                }
+            }
+
+            // Sortering
+            div {
+                style: "display: flex; gap: 8px; flex-wrap: wrap;",
+                for sort in SORTS {
+                    span {
+                        key: "{sort.label()}",
+                        class: "label",
+                        style: if (vm().sort)() == sort { "cursor: pointer; opacity: 1; text-decoration: underline;" } else { "cursor: pointer; opacity: 0.6;" },
+                        onclick: move |_| {
+                            let mut current = vm().sort;
+                            current.set(sort);
+                            spawn(async move {
+                                vm().reload(auth).await;
+                            });
+                        },
+                        "{sort.label()}"
+                    }
+                }
+            }
+
+            // Populaire tags; nog eens klikken zet het filter uit
+            if !(vm().tags)().is_empty() {
+                div {
+                    style: "display: flex; gap: 6px; flex-wrap: wrap;",
+                    for tag in (vm().tags)() {
+                        span {
+                            key: "{tag.tag}",
+                            style: if (vm().tag)().as_deref() == Some(tag.tag.as_str()) {
+                                "padding: 4px 10px; border-radius: 12px; font-size: 13px; cursor: pointer; background: var(--primary, #f5a623); color: #000;"
+                            } else {
+                                "padding: 4px 10px; border-radius: 12px; font-size: 13px; cursor: pointer; background: rgba(255,255,255,0.08);"
+                            },
+                            onclick: {
+                                let name = tag.tag.clone();
+                                move |_| {
+                                    let mut current = vm().tag;
+                                    let selected = current() == Some(name.clone());
+                                    current.set(if selected { None } else { Some(name.clone()) });
+                                    spawn(async move {
+                                        vm().reload(auth).await;
+                                    });
+                                }
+                            },
+                            "#{tag.tag} ({tag.club_count})"
+                        }
+                    }
+                }
+            }
+
+            if let Some(msg) = (vm().error_msg)() {
+                div {
+                    style: "width: 100%; padding: 12px; background: rgba(255,0,0,0.1); border: 1px solid rgba(255,0,0,0.3); border-radius: 8px; color: #ff5555; font-size: 14px;",
+                    "{msg}"
+                }
+            }
+            if let Some(msg) = (vm().info_msg)() {
+                div {
+                    style: "width: 100%; padding: 12px; background: rgba(0,255,0,0.08); border: 1px solid rgba(0,255,0,0.25); border-radius: 8px; font-size: 14px;",
+                    "{msg}"
+                }
+            }
+
+            if (vm().clubs)().is_empty() && !(vm().is_loading)() {
+                p { style: "opacity: 0.7;", "Geen clubs gevonden. Probeer een andere zoekterm of begin er zelf een!" }
+            }
+
+            for club in (vm().clubs)() {
+                div {
+                    key: "{club.id}",
+                    style: "padding: 14px; border-radius: 12px; background: rgba(255,255,255,0.05); display: flex; flex-direction: column; gap: 6px;",
+                    div {
+                        style: "display: flex; justify-content: space-between; align-items: center; gap: 8px;",
+                        h3 { style: "margin: 0;", "{club.name}" }
+                        span { style: "font-size: 13px; opacity: 0.7; white-space: nowrap;", "👥 {club.member_count}" }
+                    }
+                    if let Some(description) = &club.description {
+                        p { style: "margin: 0; font-size: 14px; opacity: 0.85;", "{description}" }
+                    }
+                    if !club.tags.is_empty() {
+                        div {
+                            style: "display: flex; gap: 6px; flex-wrap: wrap; font-size: 12px; opacity: 0.7;",
+                            for tag in club.tags.iter() {
+                                span { key: "{tag}", "#{tag}" }
+                            }
+                        }
+                    }
+                    match club.my_status.as_deref() {
+                        Some("ACTIVE") => rsx! {
+                            span { style: "align-self: flex-end; font-size: 13px; opacity: 0.7;", "✓ Je bent lid" }
+                        },
+                        Some("PENDING") => rsx! {
+                            span { style: "align-self: flex-end; font-size: 13px; opacity: 0.7;", "Verzoek verstuurd" }
+                        },
+                        // Verbannen: geen knop, de server zou toch weigeren
+                        Some(_) => rsx! {},
+                        None => rsx! {
+                            button {
+                                class: "glass-btn",
+                                style: "align-self: flex-end; width: auto; padding: 6px 16px;",
+                                onclick: {
+                                    let club = club.clone();
+                                    move |_| {
+                                        let club = club.clone();
+                                        spawn(async move {
+                                            vm().join(auth, club).await;
+                                        });
+                                    }
+                                },
+                                if club.visibility == "REQUEST" { "Verzoek sturen" } else { "Lid worden" }
+                            }
+                        },
+                    }
+                }
+            }
+
+            if (vm().is_loading)() {
+                div { style: "align-self: center;", div { class: "spinner" } }
+            } else if (vm().has_more)() {
+                button {
+                    class: "glass-btn",
+                    onclick: move |_| {
+                        spawn(async move {
+                            vm().load_more(auth).await;
+                        });
+                    },
+                    "Meer laden"
+                }
+            }
+        }
+    }
+}
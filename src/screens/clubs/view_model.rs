@@ -0,0 +1,127 @@
+use dioxus::prelude::*;
+use crate::AuthState;
+use crate::api::clubs::{self, ClubSort, ClubSummary, TagCount};
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct ClubsViewModel {
+    pub search: Signal<String>,
+    pub sort: Signal<ClubSort>,
+    pub tag: Signal<Option<String>>,
+    pub clubs: Signal<Vec<ClubSummary>>,
+    pub tags: Signal<Vec<TagCount>>,
+    pub has_more: Signal<bool>,
+    pub error_msg: Signal<Option<String>>,
+    pub info_msg: Signal<Option<String>>,
+    pub is_loading: Signal<bool>,
+    // Telt elke load op; een antwoord van een oudere load (ander filter) wordt weggegooid
+    generation: Signal<u64>,
+}
+
+impl ClubsViewModel {
+    pub fn new() -> Self {
+        Self {
+            search: Signal::new(String::new()),
+            sort: Signal::new(ClubSort::Newest),
+            tag: Signal::new(None),
+            clubs: Signal::new(Vec::new()),
+            tags: Signal::new(Vec::new()),
+            has_more: Signal::new(false),
+            error_msg: Signal::new(None),
+            info_msg: Signal::new(None),
+            is_loading: Signal::new(false),
+            generation: Signal::new(0),
+        }
+    }
+
+    /// Eerste pagina opnieuw laden, bijv. na een andere zoekterm, sortering of tag.
+    pub async fn reload(&self, auth: Signal<AuthState>) {
+        self.load(auth, true).await;
+    }
+
+    pub async fn load_more(&self, auth: Signal<AuthState>) {
+        self.load(auth, false).await;
+    }
+
+    async fn load(&self, auth: Signal<AuthState>, reset: bool) {
+        let mut is_loading = self.is_loading;
+        let mut error_msg = self.error_msg;
+        let mut clubs = self.clubs;
+        let mut has_more = self.has_more;
+        let mut generation = self.generation;
+
+        let Some(token) = auth().token else {
+            return;
+        };
+        // Een nieuwe zoekopdracht gaat altijd voor; alleen "meer laden" wacht op de lopende
+        if !reset && is_loading() {
+            return;
+        }
+
+        let current = generation() + 1;
+        generation.set(current);
+        is_loading.set(true);
+        error_msg.set(None);
+
+        let offset = if reset { 0 } else { clubs.read().len() as i32 };
+        let tag = (self.tag)();
+
+        let result = clubs::search_clubs(&token, &(self.search)(), tag.as_deref(), (self.sort)(), offset).await;
+        if generation() != current {
+            return;
+        }
+
+        match result {
+            Ok(page) => {
+                if reset {
+                    clubs.set(page.clubs);
+                } else {
+                    clubs.write().extend(page.clubs);
+                }
+                has_more.set(page.has_more);
+            }
+            Err(msg) => error_msg.set(Some(msg)),
+        }
+        is_loading.set(false);
+    }
+
+    pub async fn load_tags(&self, auth: Signal<AuthState>) {
+        let mut tags = self.tags;
+        let Some(token) = auth().token else {
+            return;
+        };
+
+        // Zonder tags werkt het scherm ook, dus een fout hier laten we stil
+        if let Ok(popular) = clubs::popular_tags(&token).await {
+            tags.set(popular);
+        }
+    }
+
+    pub async fn join(&self, auth: Signal<AuthState>, club: ClubSummary) {
+        let mut error_msg = self.error_msg;
+        let mut info_msg = self.info_msg;
+        let Some(token) = auth().token else {
+            return;
+        };
+
+        error_msg.set(None);
+        if let Err(msg) = clubs::join_club(&token, club.id).await {
+            error_msg.set(Some(msg));
+            return;
+        }
+
+        // Zonder uitnodigingscode wordt lid worden van een REQUEST club altijd een verzoek
+        let pending = club.visibility == "REQUEST";
+
+        // Knop meteen bijwerken zonder de hele lijst opnieuw te laden
+        let mut clubs = self.clubs;
+        if let Some(listed) = clubs.write().iter_mut().find(|listed| listed.id == club.id) {
+            listed.my_status = Some(if pending { "PENDING" } else { "ACTIVE" }.to_string());
+        }
+
+        if pending {
+            info_msg.set(Some(format!("Verzoek verstuurd naar {}. Een beheerder moet je nog goedkeuren.", club.name)));
+        } else {
+            info_msg.set(Some(format!("Welkom bij {}! 🍻", club.name)));
+        }
+    }
+}
@@ -92,11 +92,19 @@ impl LoginViewModel {
                     Ok(body) => {
                         if let Some(data) = body.data {
                             if let Some(success) = data.login_user {
-                                let mut auth_write = auth.write();
-                                auth_write.token = Some(success.token);
-                                auth_write.user_id = Some(success.user.id);
-                                auth_write.is_verified = success.user.is_verified;
-                                crate::api::storage::save_auth_state(&auth_write);
+                                {
+                                    let mut auth_write = auth.write();
+                                    auth_write.token = Some(success.token.clone());
+                                    auth_write.user_id = Some(success.user.id);
+                                    auth_write.is_verified = success.user.is_verified;
+                                    crate::api::storage::save_auth_state(&auth_write);
+                                }
+
+                                // Zonder gepubliceerde sleutel kan niemand de clubsleutel voor ons inpakken.
+                                // Lukt het niet, dan proberen we het bij de volgende login opnieuw.
+                                if let Ok(identity) = crate::api::e2e::Identity::load_or_create(success.user.id) {
+                                    let _ = crate::api::e2e::publish_public_key(&success.token, &identity).await;
+                                }
                             }
                         } else if let Some(errors) = body.errors {
                             error_msg.set(Some(errors[0].message.clone()));